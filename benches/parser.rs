@@ -0,0 +1,106 @@
+use adventofcode_2024::parser::{Parser, SliceParser};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+/// A megabyte or so of lines of whitespace separated integers, like days 1, 2 and 7.
+fn integers() -> String {
+    (0..100_000)
+        .map(|i: i64| format!("{} {} {}\n", i * 7919 % 100_000, -i, i % 13))
+        .collect()
+}
+
+/// Memory full of `mul(a,b)`, `do()` and `don't()` instructions amongst noise, like day 3.
+fn instructions() -> String {
+    (0..100_000)
+        .map(|i| match i % 4 {
+            0 => format!("mul({},{})", i % 1000, i % 7),
+            1 => "do()".to_string(),
+            2 => "don't()".to_string(),
+            _ => "%&why(".to_string(),
+        })
+        .collect()
+}
+
+fn benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parser");
+
+    let input = integers();
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.bench_function("next_integer", |b| {
+        b.iter_batched(
+            || Parser::from(input.as_bytes()),
+            |mut parser| {
+                let mut sum = 0;
+                while parser.eof().is_none() {
+                    while let Some(n) = parser.next_integer() {
+                        sum += n;
+                    }
+                    parser.take_newline();
+                }
+                sum
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("next_integer (slice)", |b| {
+        b.iter(|| {
+            let mut parser = SliceParser::new(&input);
+            let mut sum = 0;
+            while parser.eof().is_none() {
+                while let Some(n) = parser.next_integer() {
+                    sum += n;
+                }
+                parser.take_newline();
+            }
+            sum
+        })
+    });
+
+    group.bench_function("peek_n", |b| {
+        b.iter_batched(
+            || Parser::from(input.as_bytes()),
+            |mut parser| {
+                let mut spaces = 0;
+                while parser.peek().is_some() {
+                    spaces += parser.peek_n(4).iter().filter(|c| **c == ' ').count();
+                    parser.next();
+                }
+                spaces
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("chars", |b| {
+        b.iter_batched(
+            || Parser::from(input.as_bytes()),
+            |parser| parser.chars().filter(|c| matches!(c, Ok('\n'))).count(),
+            BatchSize::SmallInput,
+        )
+    });
+
+    let input = instructions();
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.bench_function("take_matching", |b| {
+        b.iter_batched(
+            || Parser::from(input.as_bytes()),
+            |mut parser| {
+                let mut matched = 0;
+                while parser.peek().is_some() {
+                    if parser.take_matching(["mul(", "do()", "don't()"]).is_some() {
+                        matched += 1;
+                    } else {
+                        parser.next();
+                    }
+                }
+                matched
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark);
+criterion_main!(benches);
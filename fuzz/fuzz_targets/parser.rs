@@ -0,0 +1,62 @@
+#![no_main]
+
+use adventofcode_2024::parser::{Keywords, Parser};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // The first byte says how many of the following bytes choose which methods to call, and the
+    // rest is the input they parse.
+    let Some((&ops, data)) = data.split_first() else {
+        return;
+    };
+    let (ops, input) = data.split_at(usize::from(ops).min(data.len()));
+
+    let keywords = Keywords::new([("do()", 0), ("don't()", 1), ("d", 2)]);
+    let mut parser = Parser::new(input.iter().map(|&b| Ok(b)));
+
+    for &op in ops {
+        match op % 24 {
+            0 => _ = parser.next_integer(),
+            1 => _ = parser.integer(),
+            2 => _ = parser.try_next_integer::<i32>(),
+            3 => _ = parser.next_uint(),
+            4 => _ = parser.next_hex(),
+            5 => _ = parser.next_float(),
+            6 => _ = parser.next_word(),
+            7 => _ = parser.take_matching(["mul(", "do()", "don't()"]),
+            8 => _ = parser.take_keyword(&keywords),
+            9 => _ = parser.take_pattern("mul(%d,%d)"),
+            10 => _ = parser.peek_n(usize::from(op / 24)),
+            11 => _ = parser.peek(),
+            12 => _ = parser.next(),
+            13 => _ = parser.take_newline(),
+            14 => _ = parser.eof(),
+            15 => _ = parser.skip_line(),
+            16 => _ = parser.take_until(','),
+            17 => _ = parser.separated(',', |p| p.next_integer()),
+            18 => _ = parser.separated(' ', |p| p.next_integer()),
+            19 => _ = parser.expect_newline(),
+            20 => _ = parser.grid(),
+            21 => {
+                _ = parser.seq(|p| {
+                    let left = p.next_integer()?;
+                    p.next_if_eq('|')?;
+                    p.next_integer().map(|right| (left, right))
+                })
+            }
+            22 => {
+                let checkpoint = parser.checkpoint();
+                parser.skip(usize::from(op / 24));
+                parser.restore(checkpoint);
+            }
+            _ => _ = parser.check(),
+        }
+    }
+
+    // Every character takes at least one byte, so draining the parser must end within the input.
+    let mut remaining = input.len();
+    while parser.next().is_some() {
+        assert!(remaining > 0, "parser produced more characters than input bytes");
+        remaining -= 1;
+    }
+});
@@ -0,0 +1,79 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::anyhow;
+
+use crate::toml;
+
+/// Known-good answers, loaded from a manifest such as `answers.toml`:
+///
+/// ```toml
+/// [day1]
+/// part1 = 11
+/// part2 = 31
+/// ```
+#[derive(Debug, Default)]
+pub struct Answers(HashMap<(u8, u8), String>);
+
+impl Answers {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        fs::read_to_string(path)
+            .map_err(|err| anyhow!("could not read {}: {err}", path.display()))
+            .and_then(|s| Self::parse(&s).map_err(|err| anyhow!("{}: {err}", path.display())))
+    }
+
+    pub fn parse(s: &str) -> anyhow::Result<Self> {
+        let mut answers = HashMap::new();
+
+        for entry in toml::parse(s)? {
+            let day = entry
+                .section
+                .as_deref()
+                .and_then(|section| section.strip_prefix("day"))
+                .and_then(|n| n.parse::<u8>().ok())
+                .ok_or(anyhow!("answers must be under a [dayN] section"))?;
+            let part = match entry.key.as_str() {
+                "part1" => 1,
+                "part2" => 2,
+                other => return Err(anyhow!("unexpected key '{other}' for day {day}")),
+            };
+
+            answers.insert((day, part), entry.value);
+        }
+
+        Ok(Self(answers))
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn answers_parse() {
+        let answers = Answers::parse(
+            "[day1]
+part1 = 11
+part2 = \"31\"
+
+[day07]
+part1 = 3749
+",
+        )
+        .expect("answers to parse");
+
+        assert_eq!(answers.get(1, 1), Some("11"));
+        assert_eq!(answers.get(1, 2), Some("31"));
+        assert_eq!(answers.get(7, 1), Some("3749"));
+        assert_eq!(answers.get(7, 2), None);
+    }
+
+    #[test]
+    fn answers_parse_rejects_unknown_keys() {
+        assert!(Answers::parse("[day1]\npart3 = 1").is_err());
+        assert!(Answers::parse("part1 = 1").is_err());
+    }
+}
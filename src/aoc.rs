@@ -0,0 +1,235 @@
+use std::{
+    fmt::Display,
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use anyhow::anyhow;
+
+use crate::{config::Config, error::Kind};
+
+const BASE_URL: &str = "https://adventofcode.com/2024";
+const USER_AGENT: &str = "github.com/matt-clarson/adventofcode-2024";
+
+/// A minimal client for the Advent of Code website. Requests are made by shelling out to `curl`,
+/// with the session cookie passed over stdin so it never shows up in the process list.
+pub struct Client {
+    session: String,
+}
+
+impl Client {
+    pub fn new(session: String) -> Self {
+        Self { session }
+    }
+
+    /// Create a client using the session cookie from the `AOC_SESSION` environment variable,
+    /// falling back to the one stored by `login`.
+    pub fn configured() -> anyhow::Result<Self> {
+        if let Ok(session) = std::env::var("AOC_SESSION") {
+            return Ok(Self::new(session));
+        }
+
+        Config::load()?.session()?.map(Self::new).ok_or(anyhow!(
+            "no session configured, run `login` or set AOC_SESSION"
+        ))
+    }
+
+    /// Returns the name of the user the session belongs to, or `None` if the session is not
+    /// valid.
+    pub fn whoami(&self) -> anyhow::Result<Option<String>> {
+        self.request(BASE_URL, &[]).map(|html| parse_user(&html))
+    }
+
+    /// Returns how many stars the user has collected this year, or `None` if the session is not
+    /// valid.
+    pub fn stars(&self) -> anyhow::Result<Option<u32>> {
+        self.request(BASE_URL, &[]).map(|html| parse_stars(&html))
+    }
+
+    /// Submit an answer for one part of a day's puzzle.
+    pub fn submit(&self, day: u8, part: u8, answer: &str) -> anyhow::Result<Verdict> {
+        self.request(
+            &format!("{BASE_URL}/day/{day}/answer"),
+            &[
+                "--data-urlencode",
+                &format!("level={part}"),
+                "--data-urlencode",
+                &format!("answer={answer}"),
+            ],
+        )
+        .map(|html| Verdict::parse(&html))
+    }
+
+    fn request(&self, url: &str, args: &[&str]) -> anyhow::Result<String> {
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--header", "@-"])
+            .args(["--user-agent", USER_AGENT])
+            .args(args)
+            .arg(url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| Kind::Network.error(format!("could not run curl: {err}")))?;
+
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        writeln!(stdin, "Cookie: session={}", self.session)?;
+        drop(stdin);
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(Kind::Network.error(format!(
+                "request to {url} failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+fn parse_user(html: &str) -> Option<String> {
+    let (_, rest) = html.split_once("<div class=\"user\">")?;
+    let (user, _) = rest.split_once('<')?;
+    Some(user.trim().to_string()).filter(|user| !user.is_empty())
+}
+
+fn parse_stars(html: &str) -> Option<u32> {
+    parse_user(html)?;
+    let Some((_, rest)) = html.split_once("<span class=\"star-count\">") else {
+        // The star count is left out of the header until the first star is collected.
+        return Some(0);
+    };
+    let (stars, _) = rest.split_once('*')?;
+    stars.trim().parse().ok()
+}
+
+/// The outcome of submitting an answer, as reported by the AoC website.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Incorrect,
+    Wait(String),
+    AlreadySolved,
+    Unknown,
+}
+
+impl Verdict {
+    /// Extract the verdict from the HTML page returned after submitting an answer.
+    pub fn parse(html: &str) -> Self {
+        if html.contains("That's the right answer") {
+            Self::Correct
+        } else if html.contains("your answer is too high") {
+            Self::TooHigh
+        } else if html.contains("your answer is too low") {
+            Self::TooLow
+        } else if html.contains("That's not the right answer") {
+            Self::Incorrect
+        } else if let Some(wait) = html
+            .split_once("You have ")
+            .and_then(|(_, rest)| rest.split_once(" left to wait"))
+            .map(|(wait, _)| wait.to_string())
+        {
+            Self::Wait(wait)
+        } else if html.contains("Did you already complete it?") {
+            Self::AlreadySolved
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Correct => write!(f, "correct"),
+            Self::TooHigh => write!(f, "incorrect, too high"),
+            Self::TooLow => write!(f, "incorrect, too low"),
+            Self::Incorrect => write!(f, "incorrect"),
+            Self::Wait(wait) => write!(f, "answered too recently, wait {wait}"),
+            Self::AlreadySolved => write!(f, "already solved"),
+            Self::Unknown => write!(f, "unrecognised response"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_user_from_page_header() {
+        assert_eq!(
+            parse_user(
+                "<div><div class=\"user\">matt-clarson <span class=\"star-count\">50*</span></div>"
+            ),
+            Some("matt-clarson".into())
+        );
+        assert_eq!(
+            parse_user("<a href=\"/2024/auth/login\">[Log In]</a>"),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_stars_from_page_header() {
+        assert_eq!(
+            parse_stars(
+                "<div><div class=\"user\">matt-clarson <span class=\"star-count\">44*</span></div>"
+            ),
+            Some(44)
+        );
+        assert_eq!(
+            parse_stars("<div><div class=\"user\">matt-clarson</div>"),
+            Some(0)
+        );
+        assert_eq!(
+            parse_stars("<a href=\"/2024/auth/login\">[Log In]</a>"),
+            None
+        );
+    }
+
+    #[test]
+    fn verdict_correct() {
+        assert_eq!(
+            Verdict::parse("<article><p>That's the right answer! You are one gold star closer"),
+            Verdict::Correct
+        );
+    }
+
+    #[test]
+    fn verdict_too_high_and_low() {
+        assert_eq!(
+            Verdict::parse("That's not the right answer; your answer is too high."),
+            Verdict::TooHigh
+        );
+        assert_eq!(
+            Verdict::parse("That's not the right answer; your answer is too low."),
+            Verdict::TooLow
+        );
+        assert_eq!(
+            Verdict::parse("That's not the right answer. If you're stuck"),
+            Verdict::Incorrect
+        );
+    }
+
+    #[test]
+    fn verdict_wait() {
+        assert_eq!(
+            Verdict::parse("You gave an answer too recently. You have 4m 12s left to wait."),
+            Verdict::Wait("4m 12s".into())
+        );
+    }
+
+    #[test]
+    fn verdict_already_solved() {
+        assert_eq!(
+            Verdict::parse(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            ),
+            Verdict::AlreadySolved
+        );
+    }
+}
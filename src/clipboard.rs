@@ -0,0 +1,106 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use anyhow::anyhow;
+
+/// Commands that print the system clipboard, tried in order until one runs.
+#[cfg(target_os = "macos")]
+const PASTE: &[&[&str]] = &[&["pbpaste"]];
+#[cfg(windows)]
+const PASTE: &[&[&str]] = &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard -Raw"]];
+#[cfg(not(any(target_os = "macos", windows)))]
+const PASTE: &[&[&str]] = &[
+    &["wl-paste", "--no-newline"],
+    &["xclip", "-selection", "clipboard", "-out"],
+    &["xsel", "--clipboard", "--output"],
+];
+
+/// Commands that replace the system clipboard with their stdin, tried in order until one runs.
+#[cfg(target_os = "macos")]
+const COPY: &[&[&str]] = &[&["pbcopy"]];
+#[cfg(windows)]
+const COPY: &[&[&str]] = &[&["clip"]];
+#[cfg(not(any(target_os = "macos", windows)))]
+const COPY: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard", "-in"],
+    &["xsel", "--clipboard", "--input"],
+];
+
+/// Reads the contents of the system clipboard. This shells out to the platform's clipboard tool
+/// (`pbpaste`, `wl-paste`, `xclip` or `xsel`, or PowerShell on Windows) rather than talking to
+/// the window system directly.
+pub fn paste() -> anyhow::Result<Vec<u8>> {
+    for command in PASTE {
+        let output = match Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::null())
+            .output()
+        {
+            Ok(output) => output,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(anyhow!("could not run {}: {err}", command[0])),
+        };
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "could not read the clipboard with {}: {}",
+                command[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        return Ok(output.stdout);
+    }
+
+    Err(anyhow!(
+        "could not read the clipboard, install one of: {}",
+        PASTE
+            .iter()
+            .map(|command| command[0])
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
+
+/// Replaces the contents of the system clipboard with `text`, using the same tools as [paste].
+pub fn copy(text: &str) -> anyhow::Result<()> {
+    for command in COPY {
+        let mut child = match Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(anyhow!("could not run {}: {err}", command[0])),
+        };
+
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        stdin.write_all(text.as_bytes())?;
+        drop(stdin);
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "could not write the clipboard with {}: {}",
+                command[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        return Ok(());
+    }
+
+    Err(anyhow!(
+        "could not write the clipboard, install one of: {}",
+        COPY.iter()
+            .map(|command| command[0])
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
@@ -0,0 +1,247 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::anyhow;
+
+use crate::{output::ColorChoice, toml};
+
+/// The project-local config file, read from the working directory. Its settings take precedence
+/// over the user's config file.
+pub const LOCAL_CONFIG: &str = "aoc.toml";
+
+/// Settings persisted in `~/.config/aoc2024/config.toml`, or `./aoc.toml`. These are defaults:
+/// environment variables and command line flags take precedence over them.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    /// The adventofcode.com session cookie.
+    pub session: Option<String>,
+    /// A file holding the session cookie, used when `session` is not set.
+    pub session_file: Option<PathBuf>,
+    /// Directory containing puzzle inputs named `day{N}.txt`.
+    pub input_dir: Option<PathBuf>,
+    /// The default `--output` format, e.g. `json`.
+    pub output: Option<String>,
+    /// Whether text output is coloured: `auto`, `always` or `never`.
+    pub color: Option<ColorChoice>,
+}
+
+/// Returns the directory holding the CLI's configuration, honouring `XDG_CONFIG_HOME`.
+pub fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("aoc2024"))
+}
+
+/// Returns the location of the user's config file.
+pub fn config_path() -> anyhow::Result<PathBuf> {
+    config_dir()
+        .map(|dir| dir.join("config.toml"))
+        .ok_or(anyhow!("could not locate config directory, set HOME"))
+}
+
+/// Expands a leading `~/` to the user's home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+impl Config {
+    /// Load the user's config file, overlaid with `./aoc.toml`. Missing files are treated as
+    /// empty, as is the user's config file when there is no config directory to look in.
+    pub fn load() -> anyhow::Result<Self> {
+        let user = match config_dir() {
+            Some(dir) => Self::load_from(&dir.join("config.toml"))?,
+            None => Self::default(),
+        };
+        Ok(user.merge(Self::load_from(Path::new(LOCAL_CONFIG))?))
+    }
+
+    /// Overlay `other` onto this config, keeping this config's settings where `other` has none.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            session: other.session.or(self.session),
+            session_file: other.session_file.or(self.session_file),
+            input_dir: other.input_dir.or(self.input_dir),
+            output: other.output.or(self.output),
+            color: other.color.or(self.color),
+        }
+    }
+
+    /// The session cookie, either set directly or read from `session_file`.
+    pub fn session(&self) -> anyhow::Result<Option<String>> {
+        match (&self.session, &self.session_file) {
+            (Some(session), _) => Ok(Some(session.clone())),
+            (None, Some(path)) => fs::read_to_string(path)
+                .map(|s| Some(s.trim().to_string()))
+                .map_err(|err| anyhow!("could not read {}: {err}", path.display())),
+            (None, None) => Ok(None),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> anyhow::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(s) => Self::parse(&s).map_err(|err| anyhow!("{}: {err}", path.display())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(anyhow!("could not read {}: {err}", path.display())),
+        }
+    }
+
+    /// Parse a config file. Unknown keys are ignored.
+    pub fn parse(s: &str) -> anyhow::Result<Self> {
+        let mut config = Self::default();
+
+        for entry in toml::parse(s)? {
+            if entry.section.is_some() {
+                continue;
+            }
+
+            match entry.key.as_str() {
+                "session" => config.session = Some(entry.value),
+                "session_file" => config.session_file = Some(expand_home(&entry.value)),
+                "input_dir" => config.input_dir = Some(expand_home(&entry.value)),
+                "output" => config.output = Some(entry.value),
+                "color" => config.color = Some(entry.value.parse()?),
+                _ => {}
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Write the config to `path`, readable only by the current user since it holds the session
+    /// cookie.
+    pub fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            let mut builder = fs::DirBuilder::new();
+            builder.recursive(true);
+            #[cfg(unix)]
+            std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+            builder.create(dir)?;
+        }
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let mut file = options
+            .open(path)
+            .map_err(|err| anyhow!("could not write {}: {err}", path.display()))?;
+        #[cfg(unix)]
+        file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+
+        if let Some(session) = &self.session {
+            writeln!(file, "session = \"{session}\"")?;
+        }
+        if let Some(path) = &self.session_file {
+            writeln!(file, "session_file = \"{}\"", path.display())?;
+        }
+        if let Some(dir) = &self.input_dir {
+            writeln!(file, "input_dir = \"{}\"", dir.display())?;
+        }
+        if let Some(output) = &self.output {
+            writeln!(file, "output = \"{output}\"")?;
+        }
+        if let Some(color) = &self.color {
+            writeln!(file, "color = \"{color}\"")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn config_parse_session() {
+        let config = Config::parse(
+            "# adventofcode.com
+session = \"abc123\"
+",
+        )
+        .expect("config to parse");
+
+        assert_eq!(config.session, Some("abc123".into()));
+    }
+
+    #[test]
+    fn config_parse_defaults() {
+        let config = Config::parse(
+            "input_dir = \"inputs\"
+output = \"json\"
+color = \"never\"
+",
+        )
+        .expect("config to parse");
+
+        assert_eq!(
+            config,
+            Config {
+                input_dir: Some("inputs".into()),
+                output: Some("json".into()),
+                color: Some(ColorChoice::Never),
+                ..Config::default()
+            }
+        );
+    }
+
+    #[test]
+    fn config_merge_prefers_overlay() {
+        let global = Config {
+            session: Some("abc123".into()),
+            output: Some("json".into()),
+            ..Config::default()
+        };
+        let local = Config {
+            output: Some("text".into()),
+            ..Config::default()
+        };
+
+        assert_eq!(
+            global.merge(local),
+            Config {
+                session: Some("abc123".into()),
+                output: Some("text".into()),
+                ..Config::default()
+            }
+        );
+    }
+
+    #[test]
+    fn config_save_round_trips() {
+        let path = std::env::temp_dir().join(format!("aoc2024-config-{}.toml", std::process::id()));
+        let config = Config {
+            session: Some("abc123".into()),
+            input_dir: Some("inputs".into()),
+            color: Some(ColorChoice::Always),
+            ..Config::default()
+        };
+
+        config.save_to(&path).expect("config to be saved");
+        let loaded = Config::load_from(&path).expect("config to load");
+        fs::remove_file(&path).expect("config to be removed");
+
+        assert_eq!(loaded, config);
+    }
+
+    #[test]
+    fn config_parse_ignores_unknown_keys() {
+        let config = Config::parse("colour = \"always\"").expect("config to parse");
+
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn config_parse_rejects_malformed_lines() {
+        assert!(Config::parse("session").is_err());
+    }
+}
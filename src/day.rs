@@ -1,10 +1,63 @@
-use std::io::{stdin, BufRead, StdinLock};
+use std::{
+    io::BufRead,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::error::Kind;
+
+static EXPLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Turn on explanations for the rest of the process, e.g. from `--explain`. Days that support
+/// them narrate how they reach their answer to stderr, through [explain].
+pub fn enable_explanations() {
+    EXPLAIN.store(true, Ordering::Relaxed);
+}
+
+pub fn explaining() -> bool {
+    EXPLAIN.load(Ordering::Relaxed)
+}
+
+/// Writes one step of a part's reasoning when explanations are turned on. The line is built
+/// lazily, so explaining costs nothing otherwise.
+///
+/// ```text
+/// explain(|| format!("update {update} reordered to {sorted}; middle {middle}"));
+/// ```
+pub fn explain(line: impl FnOnce() -> String) {
+    if explaining() {
+        eprintln!("{}", line());
+    }
+}
 
 pub type PartFn<I> = fn(input: I) -> anyhow::Result<String>;
 
+/// A sample input from the puzzle text, along with the answer it should produce.
+#[derive(Debug, Clone, Copy)]
+pub struct Example {
+    pub input: &'static str,
+    pub expected: &'static str,
+}
+
+/// An alternative implementation of a part, e.g. a brute force version kept around to check an
+/// optimised one against.
+pub struct Variant<I> {
+    pub part: u8,
+    pub name: &'static str,
+    pub part_fn: PartFn<I>,
+}
+
+/// A day's solution: its part functions, along with the examples and alternative implementations
+/// registered for them. This is the one type every day module returns from `solution()` and the
+/// `days!` macro registers, so tooling only has to deal with `Day`, or
+/// [Solution](crate::runner::Solution) for the CLI's input type.
+///
+/// It is a struct built up from plain functions rather than a trait for days to implement. Every
+/// day has the same shape, and a trait would only add a type per day for the registry to box.
 pub struct Day<I: BufRead> {
     part_1_fn: PartFn<I>,
     part_2_fn: Option<PartFn<I>>,
+    examples: [Option<Example>; 2],
+    variants: Vec<Variant<I>>,
 }
 
 impl<I: BufRead> Day<I> {
@@ -12,32 +65,72 @@ impl<I: BufRead> Day<I> {
         Self {
             part_1_fn,
             part_2_fn: None,
+            examples: [None; 2],
+            variants: vec![],
         }
     }
 
+    pub fn variant_1(mut self, name: &'static str, part_fn: PartFn<I>) -> Self {
+        self.variants.push(Variant {
+            part: 1,
+            name,
+            part_fn,
+        });
+        self
+    }
+
+    pub fn variant_2(mut self, name: &'static str, part_fn: PartFn<I>) -> Self {
+        self.variants.push(Variant {
+            part: 2,
+            name,
+            part_fn,
+        });
+        self
+    }
+
+    pub fn example_1(mut self, input: &'static str, expected: &'static str) -> Self {
+        self.examples[0].replace(Example { input, expected });
+        self
+    }
+
+    pub fn example_2(mut self, input: &'static str, expected: &'static str) -> Self {
+        self.examples[1].replace(Example { input, expected });
+        self
+    }
+
     pub fn part_2(mut self, part_2_fn: PartFn<I>) -> Self {
         self.part_2_fn.replace(part_2_fn);
         self
     }
-}
 
-impl Day<StdinLock<'_>> {
-    pub fn solve_part_1(&self) -> anyhow::Result<()> {
-        Self::solve(self.part_1_fn)
+    pub fn has_part_2(&self) -> bool {
+        self.part_2_fn.is_some()
     }
 
-    pub fn solve_part_2(&self) -> anyhow::Result<()> {
-        self.part_2_fn
-            .ok_or(anyhow::anyhow!("part 2 not defined"))
-            .and_then(Self::solve)
+    /// The example registered for `part` (1 or 2), if any.
+    pub fn example(&self, part: u8) -> Option<Example> {
+        match part {
+            1 | 2 => self.examples[part as usize - 1],
+            _ => None,
+        }
     }
 
-    fn solve(part_fn: PartFn<StdinLock<'_>>) -> anyhow::Result<()> {
-        let input = stdin();
-        let handle = input.lock();
+    /// The alternative implementations registered for `part` (1 or 2).
+    pub fn variants(&self, part: u8) -> impl Iterator<Item = &Variant<I>> {
+        self.variants
+            .iter()
+            .filter(move |variant| variant.part == part)
+    }
+
+    /// Run part one against `input`, returning the answer rather than printing it.
+    pub fn run_part_1(&self, input: I) -> anyhow::Result<String> {
+        (self.part_1_fn)(input)
+    }
 
-        (part_fn)(handle).map(|output| {
-            println!("{output}");
-        })
+    /// Run part two against `input`, returning the answer rather than printing it.
+    pub fn run_part_2(&self, input: I) -> anyhow::Result<String> {
+        self.part_2_fn
+            .ok_or_else(|| Kind::Unimplemented.error("part 2 not defined"))
+            .and_then(|part_fn| (part_fn)(input))
     }
 }
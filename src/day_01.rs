@@ -4,7 +4,7 @@ use std::{
     iter::zip,
 };
 
-use anyhow::anyhow;
+use anyhow::Context;
 
 use crate::{
     day::Day,
@@ -27,21 +27,20 @@ impl<R: Read> Iterator for Pairs<R> {
     type Item = anyhow::Result<(i64, i64)>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let left = self.parser.next_integer()?;
-
-        let right = if let Some(n) = self.parser.next_integer() {
-            n
-        } else {
-            return Some(Err(anyhow!("expect two integers per-line")));
+        let Some(left) = self.parser.next_integer() else {
+            return self.parser.check().err().map(Err);
         };
 
-        self.parser
-            .take_newline()
-            .or_else(|| self.parser.eof())
-            .and(Some(Ok((left, right))))
-            .or(Some(Err(anyhow!(
-                "expected line to end after second integer"
-            ))))
+        let right = self.parser.expect_integer().and_then(|right| {
+            self.parser.expect_newline()?;
+            Ok(right)
+        });
+
+        Some(
+            right
+                .map(|right| (left, right))
+                .context("expected two integers per line"),
+        )
     }
 }
 
@@ -90,23 +89,28 @@ fn part_2<I: BufRead>(input: I) -> anyhow::Result<String> {
     Ok(format!("{sum}"))
 }
 
+const EXAMPLE: &str = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3";
+
 pub fn solution<I: BufRead>() -> Day<I> {
-    Day::part_1(part_1).part_2(part_2)
+    Day::part_1(part_1)
+        .example_1(EXAMPLE, "11")
+        .part_2(part_2)
+        .example_2(EXAMPLE, "31")
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::test_util::test_solution;
+    use crate::test_solution;
 
     test_solution! {
         part_1 part_one_default_test_case
-        "3   4
-4   3
-2   5
-1   3
-3   9
-3   3",
+        EXAMPLE,
         "11"
     }
 
@@ -128,12 +132,7 @@ mod test {
 
     test_solution! {
         part_2 part_two_default_test_case
-        "3   4
-4   3
-2   5
-1   3
-3   9
-3   3",
+        EXAMPLE,
         "31"
     }
 }
@@ -1,45 +1,12 @@
-use anyhow::anyhow;
-use std::io::{BufRead, Read};
+use std::io::BufRead;
 
-use crate::{
-    day::Day,
-    parser::{BytesParser, Parser},
-};
+use crate::{day::Day, parser::SliceParser};
 
-struct Integers<R: Read> {
-    parser: BytesParser<R>,
-}
-
-impl<R: Read> From<R> for Integers<R> {
-    fn from(value: R) -> Self {
-        Self {
-            parser: Parser::from(value),
-        }
-    }
-}
-
-impl<R: Read> Iterator for Integers<R> {
-    type Item = anyhow::Result<Vec<i64>>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let mut integers = self.parser.next_integer().map(|n| vec![n])?;
-
-        while self
-            .parser
-            .take_newline()
-            .or_else(|| self.parser.eof())
-            .is_none()
-        {
-            if let Some(n) = self.parser.next_integer() {
-                integers.push(n);
-            } else {
-                return Some(Err(anyhow!(
-                    "line can only contain integers and whitespace"
-                )));
-            }
-        }
-        Some(Ok(integers))
-    }
+/// Each report is a line of levels. Inputs are small, so this reads the whole input up front.
+fn reports<I: BufRead>(mut input: I) -> anyhow::Result<Vec<Vec<i64>>> {
+    let mut s = String::new();
+    input.read_to_string(&mut s)?;
+    SliceParser::new(&s).int_table()
 }
 
 #[derive(Eq, PartialEq)]
@@ -77,9 +44,10 @@ fn part_1<I: BufRead>(input: I) -> anyhow::Result<String> {
         true
     }
 
-    let num_safe = Integers::from(input).try_fold(0, |acc, ints| {
-        ints.map(|ints| if line_is_safe(&ints) { acc + 1 } else { acc })
-    })?;
+    let num_safe = reports(input)?
+        .iter()
+        .filter(|ints| line_is_safe(ints))
+        .count();
     Ok(format!("{num_safe}"))
 }
 
@@ -102,9 +70,7 @@ fn part_2<I: BufRead>(input: I) -> anyhow::Result<String> {
 
     let mut v = Vec::with_capacity(10);
     let mut n = 0;
-    for ints in Integers::from(input) {
-        let ints = ints?;
-
+    for ints in reports(input)? {
         if line_is_safe(&ints) {
             n += 1;
             continue;
@@ -129,8 +95,18 @@ fn part_2<I: BufRead>(input: I) -> anyhow::Result<String> {
     Ok(n.to_string())
 }
 
+const EXAMPLE: &str = "7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9";
+
 pub fn solution<I: BufRead>() -> Day<I> {
-    Day::part_1(part_1).part_2(part_2)
+    Day::part_1(part_1)
+        .example_1(EXAMPLE, "2")
+        .part_2(part_2)
+        .example_2(EXAMPLE, "4")
 }
 
 #[cfg(test)]
@@ -140,23 +116,13 @@ mod test {
 
     test_solution! {
         part_1 part_one_default_case
-        "7 6 4 2 1
-1 2 7 8 9
-9 7 6 2 1
-1 3 2 4 5
-8 6 4 4 1
-1 3 6 7 9",
+        EXAMPLE,
         "2"
     }
 
     test_solution! {
         part_2 part_two_default_case
-        "7 6 4 2 1
-1 2 7 8 9
-9 7 6 2 1
-1 3 2 4 5
-8 6 4 4 1
-1 3 6 7 9",
+        EXAMPLE,
         "4"
     }
 }
@@ -2,9 +2,10 @@ use std::io::{BufRead, Read};
 
 use crate::{
     day::Day,
-    parser::{BytesParser, Parser},
+    parser::{BytesParser, Capture, Keywords, Parser},
 };
 
+#[derive(Clone, Copy)]
 enum Instruction {
     Do,
     Dont,
@@ -13,33 +14,28 @@ enum Instruction {
 
 struct Instructions<R: Read> {
     parser: BytesParser<R>,
+    switches: Keywords<Instruction>,
 }
 
 impl<R: Read> From<R> for Instructions<R> {
     fn from(value: R) -> Self {
         Self {
             parser: Parser::from(value),
+            switches: Keywords::new([("do()", Instruction::Do), ("don't()", Instruction::Dont)]),
         }
     }
 }
 
 impl<R: Read> Instructions<R> {
     fn instr(&mut self) -> Option<anyhow::Result<Instruction>> {
-        match self.parser.take_matching(["mul(", "do()", "don't()"])? {
-            "mul(" => {
-                let left = self.parser.integer()?;
-                let right = self
-                    .parser
-                    .next_if_eq(',')
-                    .and_then(|_| self.parser.integer())?;
-                self.parser
-                    .next_if_eq(')')
-                    .map(|_| Ok(Instruction::Mul(left, right)))
-            }
-            "do()" => Some(Ok(Instruction::Do)),
-            "don't()" => Some(Ok(Instruction::Dont)),
-            _ => unreachable!(),
+        if let Some(args) = self.parser.take_pattern("mul(%d,%d)") {
+            let [Capture::Integer(left), Capture::Integer(right)] = args[..] else {
+                unreachable!("pattern captures two integers")
+            };
+            return Some(Ok(Instruction::Mul(left, right)));
         }
+
+        self.parser.take_keyword(&self.switches).copied().map(Ok)
     }
 }
 
@@ -54,7 +50,7 @@ impl<R: Read> Iterator for Instructions<R> {
                 self.parser.next();
             }
         }
-        None
+        self.parser.check().err().map(Err)
     }
 }
 
@@ -85,8 +81,16 @@ fn part_2<I: BufRead>(input: I) -> anyhow::Result<String> {
         .map(|n| n.to_string())
 }
 
+const EXAMPLE_1: &str = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))
+    ";
+
+const EXAMPLE_2: &str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
 pub fn solution<I: BufRead>() -> Day<I> {
-    Day::part_1(part_1).part_2(part_2)
+    Day::part_1(part_1)
+        .example_1(EXAMPLE_1, "161")
+        .part_2(part_2)
+        .example_2(EXAMPLE_2, "48")
 }
 
 #[cfg(test)]
@@ -96,15 +100,13 @@ mod test {
 
     test_solution! {
         part_1 part_one_default_case
-        "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))
-    ",
+        EXAMPLE_1,
         "161"
-
     }
 
     test_solution! {
         part_2 part_two_default_case
-        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))",
+        EXAMPLE_2,
         "48"
     }
 }
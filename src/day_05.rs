@@ -1,117 +1,15 @@
 use std::{
     collections::{HashMap, HashSet},
-    io::{BufRead, Read},
+    io::BufRead,
 };
 
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 
 use crate::{
-    day::Day,
-    parser::{BytesParser, Parser},
+    day::{self, Day},
+    parser::{Parser, SliceParser},
 };
 
-#[derive(Debug)]
-enum SafetyUpdate {
-    Ordering(i64, i64),
-    Break,
-    Instructions(Vec<i64>),
-}
-
-struct SafetyUpdates<R: Read> {
-    parser: BytesParser<R>,
-}
-
-impl<R: Read> From<R> for SafetyUpdates<R> {
-    fn from(value: R) -> Self {
-        Self {
-            parser: Parser::from(value),
-        }
-    }
-}
-
-impl<R: Read> SafetyUpdates<R> {
-    fn take_break(&mut self) -> Option<anyhow::Result<SafetyUpdate>> {
-        if self.parser.take_newline().is_some() {
-            Some(Ok(SafetyUpdate::Break))
-        } else {
-            None
-        }
-    }
-
-    fn take_ordering(&mut self) -> Option<anyhow::Result<SafetyUpdate>> {
-        let is_ordering = self
-            .parser
-            .peek_n(5)
-            .as_bytes()
-            .get(2)
-            .filter(|b| char::from(**b) == '|')
-            .is_some();
-
-        if !is_ordering {
-            return None;
-        }
-
-        let left = self
-            .parser
-            .next_integer()
-            .expect("ordering line: next characters should be integer");
-        self.parser
-            .next_if_eq('|')
-            .expect("ordering line next character should be '|'");
-        let right = self
-            .parser
-            .next_integer()
-            .expect("ordering line: next characters should be integer");
-
-        self.parser
-            .take_newline()
-            .or_else(|| self.parser.eof())
-            .expect("ordering line: should end on newline/EOF");
-
-        Some(Ok(SafetyUpdate::Ordering(left, right)))
-    }
-
-    /// Must be called _after_ take_ordering.
-    fn take_instruction(&mut self) -> Option<anyhow::Result<SafetyUpdate>> {
-        let mut v = if let Some(n) = self.parser.next_integer() {
-            vec![n]
-        } else {
-            return None;
-        };
-
-        while self
-            .parser
-            .take_newline()
-            .or_else(|| self.parser.eof())
-            .is_none()
-        {
-            if let Some(n) = self
-                .parser
-                .next_if_eq(',')
-                .and_then(|_| self.parser.next_integer())
-            {
-                v.push(n);
-            } else {
-                return Some(Err(anyhow!(
-                    "instruction must be a sequence of integer and ',' pairs."
-                )));
-            }
-        }
-
-        Some(Ok(SafetyUpdate::Instructions(v)))
-    }
-}
-
-impl<R: Read> Iterator for SafetyUpdates<R> {
-    type Item = anyhow::Result<SafetyUpdate>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.take_break()
-            .or_else(|| self.take_ordering())
-            .or_else(|| self.take_instruction())
-    }
-}
-
 #[derive(Debug)]
 struct Ordering {
     map: HashMap<i64, HashSet<i64>>,
@@ -162,90 +60,97 @@ impl Ordering {
 
     fn get_middle_if_sorted(&self, xs: Vec<i64>) -> Option<i64> {
         if !self.is_sorted(&xs) {
+            day::explain(|| format!("update {} out of order; skipped", join(&xs)));
             return None;
         }
 
-        Some(xs[xs.len() / 2])
+        let middle = xs[xs.len() / 2];
+        day::explain(|| format!("update {} in order; middle {middle}", join(&xs)));
+        Some(middle)
     }
 
     fn get_middle_if_not_sorted(&self, mut xs: Vec<i64>) -> Option<i64> {
         if self.is_sorted(&xs) {
+            day::explain(|| format!("update {} already in order; skipped", join(&xs)));
             return None;
         }
 
-        xs.sort_unstable_by(|left, right| self.compare(*left, *right));
-        Some(xs[xs.len() / 2])
+        let unsorted = day::explaining().then(|| join(&xs));
+        xs.sort_unstable_by(|left, right| self.compare(*right, *left));
+
+        let middle = xs[xs.len() / 2];
+        day::explain(|| {
+            format!(
+                "update {} reordered to {}; middle {middle}",
+                unsorted.unwrap_or_default(),
+                join(&xs)
+            )
+        });
+        Some(middle)
     }
 }
 
-fn part_1<I: BufRead>(input: I) -> anyhow::Result<String> {
-    let mut safety_updates = SafetyUpdates::from(input);
-    let mut ordering = Ordering::new();
+fn join(xs: &[i64]) -> String {
+    xs.iter().map(i64::to_string).collect::<Vec<_>>().join(",")
+}
 
-    for update in safety_updates
-        .by_ref()
-        .take_while(|u| !matches!(u, Ok(SafetyUpdate::Break)))
-    {
-        match update? {
-            SafetyUpdate::Ordering(left, right) => ordering.insert((left, right)),
-            other => return Err(anyhow!("unexpected safety update entry: {other:?}")),
-        };
+/// Reads the page ordering rules and the updates, which are separated by a blank line.
+fn read<I: BufRead>(input: I) -> anyhow::Result<(Ordering, Vec<Vec<i64>>)> {
+    let mut parser = Parser::from(input);
+    let mut sections = parser.sections();
+    let rules = sections.next().transpose()?.unwrap_or_default();
+    let updates = sections.next().transpose()?.unwrap_or_default();
+    if sections.next().transpose()?.is_some() {
+        return Err(anyhow!(
+            "expected ordering rules then updates, but found a third section"
+        ));
     }
 
-    safety_updates
-        .try_fold(0, |acc, update| match update? {
-            SafetyUpdate::Instructions(instructions) => {
-                if let Some(n) = ordering.get_middle_if_sorted(instructions) {
-                    Ok(acc + n)
-                } else {
-                    Ok(acc)
-                }
-            }
-            other => Err(anyhow!("unexpected safety update entry: {other:?}")),
-        })
-        .map(|n| n.to_string())
-}
-
-fn part_2<I: BufRead>(input: I) -> anyhow::Result<String> {
-    let mut safety_updates = SafetyUpdates::from(input);
     let mut ordering = Ordering::new();
+    let mut parser = SliceParser::new(&rules);
+    while parser.eof().is_none() {
+        let rule = parser.expect_integer().and_then(|left| {
+            parser.expect_char('|')?;
+            let right = parser.expect_integer()?;
+            parser.expect_newline()?;
+            Ok((left, right))
+        });
+        ordering.insert(rule.context("ordering rules must be two integers separated by '|'")?);
+    }
 
-    for update in safety_updates
-        .by_ref()
-        .take_while(|u| !matches!(u, Ok(SafetyUpdate::Break)))
-    {
-        match update? {
-            SafetyUpdate::Ordering(left, right) => ordering.insert((left, right)),
-            other => return Err(anyhow!("unexpected safety update entry: {other:?}")),
-        };
+    let mut parser = SliceParser::new(&updates);
+    let mut updates = vec![];
+    while parser.eof().is_none() {
+        let update = parser
+            .separated(',', |p| p.next_integer())
+            .context("update must be a sequence of integer and ',' pairs")?;
+        updates.push(update);
     }
 
-    safety_updates
-        .try_fold(0, |acc, update| match update? {
-            SafetyUpdate::Instructions(instructions) => {
-                if let Some(n) = ordering.get_middle_if_not_sorted(instructions) {
-                    Ok(acc + n)
-                } else {
-                    Ok(acc)
-                }
-            }
-            other => Err(anyhow!("unexpected safety update entry: {other:?}")),
-        })
-        .map(|n| n.to_string())
+    Ok((ordering, updates))
 }
 
-pub fn solution<I: BufRead>() -> Day<I> {
-    Day::part_1(part_1).part_2(part_2)
+fn part_1<I: BufRead>(input: I) -> anyhow::Result<String> {
+    let (ordering, updates) = read(input)?;
+
+    let sum: i64 = updates
+        .into_iter()
+        .filter_map(|update| ordering.get_middle_if_sorted(update))
+        .sum();
+    Ok(sum.to_string())
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::test_solution;
+fn part_2<I: BufRead>(input: I) -> anyhow::Result<String> {
+    let (ordering, updates) = read(input)?;
 
-    test_solution! {
-        part_1 part_one_default_case
-        "47|53
+    let sum: i64 = updates
+        .into_iter()
+        .filter_map(|update| ordering.get_middle_if_not_sorted(update))
+        .sum();
+    Ok(sum.to_string())
+}
+
+const EXAMPLE: &str = "47|53
 97|13
 97|61
 97|47
@@ -272,40 +177,29 @@ mod test {
 75,29,13
 75,97,47,61,53
 61,13,29
-97,13,75,29,47",
+97,13,75,29,47";
+
+pub fn solution<I: BufRead>() -> Day<I> {
+    Day::part_1(part_1)
+        .example_1(EXAMPLE, "143")
+        .part_2(part_2)
+        .example_2(EXAMPLE, "123")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_solution;
+
+    test_solution! {
+        part_1 part_one_default_case
+        EXAMPLE,
         "143"
     }
 
     test_solution! {
         part_2 part_two_default_case
-        "47|53
-97|13
-97|61
-97|47
-75|29
-61|13
-75|53
-29|13
-97|29
-53|29
-61|53
-97|53
-61|29
-47|13
-75|47
-97|75
-47|61
-75|61
-47|29
-75|13
-53|13
-
-75,47,61,53,29
-97,61,53,29,13
-75,29,13
-75,97,47,61,53
-61,13,29
-97,13,75,29,47",
+        EXAMPLE,
         "123"
     }
 }
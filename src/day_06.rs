@@ -1,129 +1,134 @@
-use std::io::{BufRead, Read};
+use std::io::BufRead;
 
 use anyhow::anyhow;
-use gxhash::HashSetExt;
-
-use crate::{day::Day, grid::Vec2};
-
-struct Steps {
-    width: usize,
-    height: usize,
-    positions: gxhash::HashSet<Vec2<usize>>,
-    obstacle: Option<Vec2<usize>>,
-    initial: Vec2<usize>,
-    current: Option<Vec2<usize>>,
-    direction: Vec2<isize>,
-}
 
-impl Steps {
-    fn try_from<R: Read>(source: R) -> anyhow::Result<Self> {
-        let mut width = 0;
-        let mut height = 0;
-        let mut positions = gxhash::HashSet::new();
-        let mut start = None;
-
-        for (i, b) in source.bytes().enumerate() {
-            match char::from(b?) {
-                '\n' => {
-                    width = if width == 0 { i } else { width };
-                    height += 1;
-                }
-                '#' => {
-                    positions.insert(Vec2(i - (height * width) - height, height));
-                }
-                '^' => start = Some(Vec2(i - (height * width) - height, height)),
-                _ => {}
-            }
-        }
+use crate::{
+    day::Day,
+    grid::{Direction, Grid2D, GridCursor, PositionSet, Vec2},
+    parser::Parser,
+};
+
+/// Reads the lab map, returning it along with the guard's starting position.
+fn read<I: BufRead>(input: I) -> anyhow::Result<(Grid2D<char>, Vec2<usize>)> {
+    let map = Parser::from(input).grid()?;
+    if map.find(|&c| c == '#').is_none() {
+        return Err(anyhow!("no block positions in map"));
+    }
+    let start = map
+        .find(|&c| c == '^')
+        .ok_or(anyhow!("no start position in map"))?;
 
-        if positions.is_empty() {
-            return Err(anyhow!("no block positions in map"));
-        }
+    Ok((map, start))
+}
 
-        start
-            .ok_or(anyhow!("no start position in map"))
-            .map(|start| Self {
-                width,
-                height,
-                positions,
-                initial: start,
-                current: None,
-                direction: Vec2(0, -1),
-                obstacle: None,
-            })
-    }
+/// Walks the guard from `start` until they leave the map, returning each position they visit, or
+/// `None` if they end up walking in a loop instead.
+fn patrol(map: &Grid2D<char>, start: Vec2<usize>) -> Option<PositionSet> {
+    let mut guard = GridCursor::new(start, Direction::Up).detect_loops();
+    let mut visited = map.position_set();
+    visited.insert(start);
 
-    fn next_step(&self) -> Option<Vec2<usize>> {
-        self.current
-            .and_then(|p| p.try_add(self.direction, Vec2(self.width, self.height)))
-    }
+    while let Some((p, c)) = guard.peek_ahead(map) {
+        if *c == '#' {
+            guard.turn_right();
+        } else {
+            guard.step(map);
+            visited.insert(p);
+        }
 
-    fn reset_with_obstacle(&mut self, obstacle: Vec2<usize>) {
-        self.current = None;
-        self.direction = Vec2(0, -1);
-        self.positions.insert(obstacle);
-        if let Some(prev) = self.obstacle.replace(obstacle) {
-            self.positions.remove(&prev);
+        if guard.looped() {
+            return None;
         }
     }
-}
 
-impl Iterator for Steps {
-    type Item = (Vec2<usize>, Vec2<isize>);
+    Some(visited)
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.current.is_none() {
-            self.current = Some(self.initial);
-            return self.current.map(|p| (p, self.direction));
-        }
+/// Whether the guard walking from `start` ends up going round in a loop.
+fn is_loop(map: &Grid2D<char>, start: Vec2<usize>) -> bool {
+    let mut guard = GridCursor::new(start, Direction::Up).detect_loops();
 
-        let next = self.next_step()?;
-        if self.positions.contains(&next) {
-            self.direction = self.direction.rotate_clockwise();
+    while let Some((_, c)) = guard.peek_ahead(map) {
+        if *c == '#' {
+            guard.turn_right();
+            if guard.looped() {
+                return true;
+            }
         } else {
-            self.current = Some(next);
+            guard.step(map);
         }
-
-        self.current.map(|p| (p, self.direction))
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (0, Some(self.width * self.height))
-    }
+    false
 }
 
 pub fn part_1<I: BufRead>(input: I) -> anyhow::Result<String> {
-    Steps::try_from(input).map(|s| {
-        s.map(|(p, _)| p)
-            .collect::<gxhash::HashSet<_>>()
-            .len()
-            .to_string()
-    })
+    let (map, start) = read(input)?;
+    let visited = patrol(&map, start).ok_or(anyhow!("guard never leaves the map"))?;
+
+    Ok(visited.len().to_string())
 }
 
 pub fn part_2<I: BufRead>(input: I) -> anyhow::Result<String> {
-    let mut steps = Steps::try_from(input)?;
-    let positions = steps
-        .by_ref()
-        .map(|(p, _)| p)
-        .collect::<gxhash::HashSet<_>>();
-
-    let mut seen = gxhash::HashSet::with_capacity(positions.len());
-
-    let num_loops = positions.iter().fold(0, |acc, p| {
-        seen.clear();
-        steps.reset_with_obstacle(*p);
-        if steps.by_ref().any(|step| !seen.insert(step)) {
-            return acc + 1;
+    let (mut map, start) = read(input)?;
+    let visited = patrol(&map, start).ok_or(anyhow!("guard never leaves the map"))?;
+    let positions: Vec<_> = visited.iter().collect();
+    crate::debug!("walked guard path", positions = positions.len());
+
+    // An obstacle can only change the guard's route if it's somewhere they would have walked.
+    let num_loops = positions
+        .iter()
+        .filter(|p| **p != start)
+        .filter(|p| {
+            map[**p] = '#';
+            let looped = is_loop(&map, start);
+            map[**p] = '.';
+            looped
+        })
+        .count();
+    crate::debug!(
+        "tried obstacles",
+        obstacles = positions.len(),
+        loops = num_loops
+    );
+
+    Ok(num_loops.to_string())
+}
+
+/// Checks every open cell on the map for a loop, rather than only those on the guard's path.
+fn part_2_bruteforce<I: BufRead>(input: I) -> anyhow::Result<String> {
+    let (mut map, start) = read(input)?;
+    let open: Vec<_> = map.positions_of(|&c| c == '.').collect();
+
+    let mut num_loops = 0;
+    for p in open {
+        map[p] = '#';
+        if is_loop(&map, start) {
+            num_loops += 1;
         }
-        acc
-    });
+        map[p] = '.';
+    }
 
     Ok(num_loops.to_string())
 }
 
+const EXAMPLE: &str = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
 pub fn solution<I: BufRead>() -> Day<I> {
-    Day::part_1(part_1).part_2(part_2)
+    Day::part_1(part_1)
+        .example_1(EXAMPLE, "41")
+        .part_2(part_2)
+        .example_2(EXAMPLE, "6")
+        .variant_2("bruteforce", part_2_bruteforce)
 }
 
 #[cfg(test)]
@@ -133,31 +138,13 @@ mod test {
 
     test_solution! {
         part_1 part_one_default_case
-        "....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#...",
+        EXAMPLE,
         "41"
     }
 
     test_solution! {
         part_2 part_two_default_case
-        "....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#...",
+        EXAMPLE,
         "6"
     }
 
@@ -169,4 +156,10 @@ mod test {
 .^#.",
         "1"
     }
+
+    test_solution! {
+        part_2_bruteforce part_two_bruteforce_default_case
+        EXAMPLE,
+        "6"
+    }
 }
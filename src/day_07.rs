@@ -1,6 +1,6 @@
 use std::io::{BufRead, Read};
 
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 
 use crate::{
     day::Day,
@@ -26,37 +26,38 @@ impl<R: Read> Iterator for Cases<R> {
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.parser.eof().is_some() {
-            return None;
+            return self.parser.check().err().map(Err);
         }
 
-        let n = if let Some(n) = self.parser.next_integer() {
-            n
-        } else {
-            return Some(Err(anyhow!("line must start with integer")));
+        let n = match self.parser.try_next_integer() {
+            Ok(Some(n)) => n,
+            Ok(None) => {
+                return Some(Err(anyhow!(
+                    "line must start with integer at {}",
+                    self.parser.position()
+                )))
+            }
+            Err(err) => return Some(Err(err)),
         };
 
-        if self.parser.next_if_eq(':').is_none() {
-            return Some(Err(anyhow!("first integer must be followed by ':'")));
+        if let Err(err) = self.parser.expect_char(':') {
+            return Some(Err(err.context("first integer must be followed by ':'")));
         }
+        let after_colon = self.parser.position();
 
-        let mut v = vec![];
-        while self
+        let v = match self
             .parser
-            .take_newline()
-            .or_else(|| self.parser.eof())
-            .is_none()
+            .separated(' ', |p| p.next_integer())
+            .context("':' can only be followed by integers and whitespace")
         {
-            if let Some(n) = self.parser.next_integer() {
-                v.push(n);
-            } else {
-                return Some(Err(anyhow!(
-                    "':' can only be followed by integers and whitespace"
-                )));
-            }
-        }
+            Ok(v) => v,
+            Err(err) => return Some(Err(err)),
+        };
 
         if v.is_empty() {
-            return Some(Err(anyhow!("need at least one integer after ':'")));
+            return Some(Err(anyhow!(
+                "need at least one integer after ':' at {after_colon}"
+            )));
         }
 
         Some(Ok(Case(n, v)))
@@ -123,8 +124,21 @@ pub fn part_2<I: BufRead>(input: I) -> anyhow::Result<String> {
         .map(|sum| sum.to_string())
 }
 
+const EXAMPLE: &str = "190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20";
+
 pub fn solution<I: BufRead>() -> Day<I> {
-    Day::part_1(part_1).part_2(part_2)
+    Day::part_1(part_1)
+        .example_1(EXAMPLE, "3749")
+        .part_2(part_2)
+        .example_2(EXAMPLE, "11387")
 }
 
 #[cfg(test)]
@@ -135,15 +149,7 @@ mod test {
 
     test_solution! {
         part_1 part_one_default_case
-        "190: 10 19
-3267: 81 40 27
-83: 17 5
-156: 15 6
-7290: 6 8 6 15
-161011: 16 10 13
-192: 17 8 14
-21037: 9 7 18 13
-292: 11 6 16 20",
+        EXAMPLE,
         "3749"
     }
 
@@ -162,15 +168,7 @@ mod test {
 
     test_solution! {
         part_2 part_two_default_case
-        "190: 10 19
-3267: 81 40 27
-83: 17 5
-156: 15 6
-7290: 6 8 6 15
-161011: 16 10 13
-192: 17 8 14
-21037: 9 7 18 13
-292: 11 6 16 20",
+        EXAMPLE,
         "11387"
     }
 
@@ -1,4 +1,4 @@
-use std::io::{BufRead, Read};
+use std::io::BufRead;
 
 use gxhash::{HashMapExt, HashSetExt};
 
@@ -11,7 +11,7 @@ struct Map {
 }
 
 impl Map {
-    fn try_from<R: Read>(source: R) -> anyhow::Result<Self> {
+    fn try_from<R: BufRead>(source: R) -> anyhow::Result<Self> {
         let mut x = 0;
         let mut y = 0;
         let mut width = 0;
@@ -111,8 +111,24 @@ pub fn part_2<I: BufRead>(input: I) -> anyhow::Result<String> {
     Ok(positions.len().to_string())
 }
 
+const EXAMPLE: &str = "............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............";
+
 pub fn solution<I: BufRead>() -> Day<I> {
-    Day::part_1(part_1).part_2(part_2)
+    Day::part_1(part_1)
+        .example_1(EXAMPLE, "14")
+        .part_2(part_2)
+        .example_2(EXAMPLE, "34")
 }
 
 #[cfg(test)]
@@ -123,35 +139,13 @@ mod test {
 
     test_solution! {
         part_1 part_one_default_case
-        "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............",
+        EXAMPLE,
         "14"
     }
 
     test_solution! {
         part_2 part_two_default_case
-        "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............",
+        EXAMPLE,
         "34"
     }
 }
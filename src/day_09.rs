@@ -23,13 +23,14 @@ impl<R: Read> From<R> for Digits<R> {
 }
 
 impl<R: Read> Iterator for Digits<R> {
-    type Item = u64;
+    type Item = anyhow::Result<u64>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.parser
-            .next_if(|c| c.is_ascii_digit())
+        match self.parser.next_if(|c| c.is_ascii_digit()) {
             // SAFTEY: checking if char is digit then parsing to unsigned int
-            .map(|c| unsafe { c.to_string().parse().unwrap_unchecked() })
+            Some(c) => Some(Ok(unsafe { c.to_string().parse().unwrap_unchecked() })),
+            None => self.parser.check().err().map(Err),
+        }
     }
 }
 
@@ -38,12 +39,14 @@ struct Files {
     idx: usize,
 }
 
-impl<R: Read> From<Digits<R>> for Files {
-    fn from(digits: Digits<R>) -> Self {
-        Self {
-            digits: digits.collect(),
-            idx: 0,
-        }
+impl<R: Read> TryFrom<Digits<R>> for Files {
+    type Error = anyhow::Error;
+
+    fn try_from(digits: Digits<R>) -> anyhow::Result<Self> {
+        let digits = digits.collect::<anyhow::Result<Vec<u64>>>()?;
+        crate::debug!("read disk map", digits = digits.len());
+
+        Ok(Self { digits, idx: 0 })
     }
 }
 
@@ -139,6 +142,8 @@ impl Files {
             }
         }
 
+        crate::debug!("compacted files", files = seen.len(), blocks = self.idx);
+
         sum
     }
 
@@ -159,17 +164,22 @@ impl Files {
 pub fn part_1<I: BufRead>(input: I) -> anyhow::Result<String> {
     let digits = Digits::from(input);
 
-    Ok(Files::from(digits).checksum().to_string())
+    Ok(Files::try_from(digits)?.checksum().to_string())
 }
 
 pub fn part_2<I: BufRead>(input: I) -> anyhow::Result<String> {
     let digits = Digits::from(input);
 
-    Ok(Files::from(digits).checksum_v2().to_string())
+    Ok(Files::try_from(digits)?.checksum_v2().to_string())
 }
 
+const EXAMPLE: &str = "2333133121414131402";
+
 pub fn solution<I: BufRead>() -> Day<I> {
-    Day::part_1(part_1).part_2(part_2)
+    Day::part_1(part_1)
+        .example_1(EXAMPLE, "1928")
+        .part_2(part_2)
+        .example_2(EXAMPLE, "2858")
 }
 
 #[cfg(test)]
@@ -180,13 +190,13 @@ mod test {
 
     test_solution! {
         part_1 part_one_default_case
-        "2333133121414131402",
+        EXAMPLE,
         "1928"
     }
 
     test_solution! {
         part_2 part_two_default_case
-        "2333133121414131402",
+        EXAMPLE,
         "2858"
     }
 }
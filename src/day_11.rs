@@ -9,16 +9,19 @@ struct Stones {
     cache: HashMap<SmolStr, u64>,
 }
 
-impl<R: Read> From<R> for Stones {
-    fn from(value: R) -> Self {
+enum Op {
+    Add(SmolStr, u64),
+    Sub(SmolStr, u64),
+}
+
+impl Stones {
+    fn read<R: Read>(value: R) -> anyhow::Result<Self> {
         let mut parser = Parser::from(value);
         let mut cache = HashMap::new();
         while parser.eof().is_none() {
             let mut s = SmolStrBuilder::new();
             parser.skip_if_eq(' ');
-            while let Some(c) = parser.next_if(|c| c.is_ascii_digit()) {
-                s.push(c);
-            }
+            parser.take_while_into(&mut s, |c| c.is_ascii_digit())?;
             let s = s.finish();
             if let Some(n) = cache.get_mut(&s) {
                 let _ = std::mem::replace(n, *n + 1);
@@ -26,25 +29,25 @@ impl<R: Read> From<R> for Stones {
                 cache.insert(s, 1);
             }
         }
+        parser.check()?;
 
-        Self { cache }
+        Ok(Self { cache })
     }
-}
 
-enum Op {
-    Add(SmolStr, u64),
-    Sub(SmolStr, u64),
-}
-
-impl Stones {
     fn iterations(&mut self, n: usize) -> u64 {
-        for _ in 0..n {
+        for i in 0..n {
             let mut c = self.cache.clone();
             c.retain(|_, v| *v > 0);
             self.step();
+            crate::debug!(
+                "blink",
+                step = i + 1,
+                cache_size = self.cache.len(),
+                stones = self.cache.values().sum::<u64>()
+            );
         }
 
-        return self.cache.values().sum();
+        self.cache.values().sum()
     }
 
     fn step(&mut self) {
@@ -96,17 +99,21 @@ impl Stones {
 }
 
 pub fn part_1<I: BufRead>(input: I) -> anyhow::Result<String> {
-    let stones = Stones::from(input).iterations(25);
+    let stones = Stones::read(input)?.iterations(25);
     Ok(stones.to_string())
 }
 
 pub fn part_2<I: BufRead>(input: I) -> anyhow::Result<String> {
-    let stones = Stones::from(input).iterations(75);
+    let stones = Stones::read(input)?.iterations(75);
     Ok(stones.to_string())
 }
 
+const EXAMPLE: &str = "125 17";
+
 pub fn solution<I: BufRead>() -> Day<I> {
-    Day::part_1(part_1).part_2(part_2)
+    Day::part_1(part_1)
+        .example_1(EXAMPLE, "55312")
+        .part_2(part_2)
 }
 
 #[cfg(test)]
@@ -117,7 +124,7 @@ mod test {
 
     test_solution! {
         part_1 part_one_default_case
-        "125 17",
+        EXAMPLE,
         "55312"
     }
 }
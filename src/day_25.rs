@@ -0,0 +1,221 @@
+use std::io::{BufRead, Read};
+
+use anyhow::anyhow;
+
+use crate::{
+    day::Day,
+    parser::{BytesParser, Parser},
+};
+
+const WIDTH: usize = 5;
+const HEIGHT: usize = 7;
+
+enum Schematic {
+    Lock([usize; WIDTH]),
+    Key([usize; WIDTH]),
+}
+
+struct Schematics<R: Read> {
+    parser: BytesParser<R>,
+}
+
+impl<R: Read> From<R> for Schematics<R> {
+    fn from(value: R) -> Self {
+        Self {
+            parser: Parser::from(value),
+        }
+    }
+}
+
+impl<R: Read> Iterator for Schematics<R> {
+    type Item = anyhow::Result<Schematic>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.parser.take_newline().is_some() {}
+
+        if self.parser.eof().is_some() {
+            return self.parser.check().err().map(Err);
+        }
+
+        let mut rows = [[false; WIDTH]; HEIGHT];
+
+        for row in rows.iter_mut() {
+            for cell in row.iter_mut() {
+                match self.parser.next() {
+                    Some('#') => *cell = true,
+                    Some('.') => {}
+                    Some('\n') | None => {
+                        return Some(Err(anyhow!(
+                            "schematic rows must be {WIDTH} characters wide"
+                        )))
+                    }
+                    Some(c) => {
+                        return Some(Err(anyhow!(
+                            "schematic can only contain '#' and '.', found {c:?}"
+                        )))
+                    }
+                }
+            }
+
+            if self
+                .parser
+                .take_newline()
+                .or_else(|| self.parser.eof())
+                .is_none()
+            {
+                return Some(Err(anyhow!(
+                    "schematic rows must be {WIDTH} characters wide"
+                )));
+            }
+        }
+
+        let mut heights = [0; WIDTH];
+        for row in rows.iter() {
+            for (height, cell) in heights.iter_mut().zip(row) {
+                if *cell {
+                    *height += 1;
+                }
+            }
+        }
+
+        let top = rows[0];
+        let bottom = rows[HEIGHT - 1];
+
+        if top.iter().all(|c| *c) && !bottom.iter().any(|c| *c) {
+            Some(Ok(Schematic::Lock(heights)))
+        } else if !top.iter().any(|c| *c) && bottom.iter().all(|c| *c) {
+            Some(Ok(Schematic::Key(heights)))
+        } else {
+            Some(Err(anyhow!(
+                "schematic must be a lock (full top row) or a key (full bottom row)"
+            )))
+        }
+    }
+}
+
+fn part_1<I: BufRead>(input: I) -> anyhow::Result<String> {
+    let mut locks = vec![];
+    let mut keys = vec![];
+
+    for schematic in Schematics::from(input) {
+        match schematic? {
+            Schematic::Lock(heights) => locks.push(heights),
+            Schematic::Key(heights) => keys.push(heights),
+        }
+    }
+
+    let fits = locks.iter().fold(0, |acc, lock| {
+        acc + keys
+            .iter()
+            .filter(|key| lock.iter().zip(key.iter()).all(|(l, k)| l + k <= HEIGHT))
+            .count()
+    });
+
+    Ok(fits.to_string())
+}
+
+const EXAMPLE: &str = "#####
+.####
+.####
+.####
+.#.#.
+.#...
+.....
+
+#####
+##.##
+.#.##
+...##
+...#.
+...#.
+.....
+
+.....
+#....
+#....
+#...#
+#.#.#
+#.###
+#####
+
+.....
+.....
+#.#..
+###..
+###.#
+###.#
+#####
+
+.....
+.....
+.....
+#....
+#.#..
+#.#.#
+#####";
+
+pub fn solution<I: BufRead>() -> Day<I> {
+    Day::part_1(part_1).example_1(EXAMPLE, "3")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_solution;
+
+    use super::*;
+
+    test_solution! {
+        part_1 part_one_default_case
+        EXAMPLE,
+        "3"
+    }
+
+    test_solution! {
+        part_1 part_one_rejects_overlapping_pairs
+        "#####
+#####
+#####
+#####
+#####
+#####
+.....
+
+.....
+#####
+#####
+#####
+#####
+#####
+#####",
+        "0"
+    }
+
+    #[test]
+    fn part_one_rejects_short_rows() {
+        let input = crate::test_util::StringBufRead::from("#####\n.####\n.##\n");
+
+        let err = part_1(input).expect_err("short row should be rejected");
+
+        assert_eq!(err.to_string(), "schematic rows must be 5 characters wide");
+    }
+
+    #[test]
+    fn part_one_rejects_schematics_without_a_full_edge() {
+        let input = crate::test_util::StringBufRead::from(
+            "####.
+.####
+.####
+.####
+.#.#.
+.#...
+.....",
+        );
+
+        let err = part_1(input).expect_err("partial top row should be rejected");
+
+        assert_eq!(
+            err.to_string(),
+            "schematic must be a lock (full top row) or a key (full bottom row)"
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt::Display;
+
+/// Categories of failure that the CLI reports with distinct exit codes, so that scripts driving
+/// it can tell them apart. Uncategorised errors exit with 1, and clap exits with 2 when the command
+/// line is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// The puzzle input could not be found.
+    InputNotFound,
+    /// A [Parser](crate::parser::Parser) failed to make sense of a part's input.
+    Parse,
+    /// The requested part has not been written yet.
+    Unimplemented,
+    /// A request to the Advent of Code website failed.
+    Network,
+    /// A part was still running when its `--timeout` ran out.
+    Timeout,
+}
+
+impl Kind {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::InputNotFound => 7,
+            Self::Parse => 3,
+            Self::Unimplemented => 4,
+            Self::Network => 5,
+            Self::Timeout => 6,
+        }
+    }
+
+    /// A machine-readable name for the kind, e.g. for JSON output.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::InputNotFound => "input_not_found",
+            Self::Parse => "parse",
+            Self::Unimplemented => "unimplemented",
+            Self::Network => "network",
+            Self::Timeout => "timeout",
+        }
+    }
+
+    /// Tag `err` with this kind. The error's message and sources are unchanged.
+    pub fn wrap(self, err: anyhow::Error) -> anyhow::Error {
+        anyhow::Error::new(Error {
+            kind: self,
+            inner: err,
+        })
+    }
+
+    /// Create an error of this kind from a message.
+    pub fn error(self, message: impl Display) -> anyhow::Error {
+        self.wrap(anyhow::Error::msg(message.to_string()))
+    }
+}
+
+/// An error tagged with a [Kind]. Displays exactly as the error it wraps.
+#[derive(Debug)]
+struct Error {
+    kind: Kind,
+    inner: anyhow::Error,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.inner)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.inner.chain().nth(1)
+    }
+}
+
+/// Returns the kind `err` was tagged with, looking through any context added since.
+pub fn kind(err: &anyhow::Error) -> Option<Kind> {
+    err.chain()
+        .find_map(|err| err.downcast_ref::<Error>())
+        .map(|err| err.kind)
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn kind_survives_context() {
+        let err = Kind::Parse
+            .wrap(anyhow!("bad digit").context("reading line 3"))
+            .context("day 9 part 1 failed");
+
+        assert_eq!(kind(&err), Some(Kind::Parse));
+        assert_eq!(
+            format!("{err:#}"),
+            "day 9 part 1 failed: reading line 3: bad digit"
+        );
+    }
+
+    #[test]
+    fn kind_of_untagged_error() {
+        assert_eq!(kind(&anyhow!("oops")), None);
+    }
+}
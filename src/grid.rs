@@ -1,21 +1,30 @@
-use std::{fmt::Debug, hash::Hash};
+use std::{
+    collections::VecDeque,
+    fmt::{Debug, Display},
+    hash::Hash,
+    ops::{Add, Deref, Index, IndexMut, Mul, Neg, Sub},
+};
+
+use anyhow::anyhow;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+mod cursor;
+mod grid3d;
+mod hex;
+
+pub use cursor::GridCursor;
+#[allow(unused)]
+pub use grid3d::{Grid3D, Vec3, DIRECTIONS_6};
+#[allow(unused)]
+pub use hex::{Cube, Hex, HEX_DIRECTIONS};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Vec2<I>(pub I, pub I);
 
 impl Vec2<usize> {
     pub fn try_add(&self, d: Vec2<isize>, max: Vec2<usize>) -> Option<Vec2<usize>> {
-        let x = if d.0.is_negative() {
-            self.0.checked_sub(d.0.wrapping_abs() as usize)
-        } else {
-            self.0.checked_add(d.0 as usize)
-        }?;
-
-        let y = if d.1.is_negative() {
-            self.1.checked_sub(d.1.wrapping_abs() as usize)
-        } else {
-            self.1.checked_add(d.1 as usize)
-        }?;
+        let Vec2(x, y) = (self.signed() + d).try_cast()?;
 
         if x > max.0 || y > max.1 {
             return None;
@@ -25,45 +34,313 @@ impl Vec2<usize> {
     }
 
     pub fn try_subtract(&self, d: Vec2<isize>, max: Vec2<usize>) -> Option<Vec2<usize>> {
-        let x = if d.0.is_negative() {
-            self.0.checked_add(d.0.wrapping_abs() as usize)
-        } else {
-            self.0.checked_sub(d.0 as usize)
-        }?;
-
-        let y = if d.1.is_negative() {
-            self.1.checked_add(d.1.wrapping_abs() as usize)
-        } else {
-            self.1.checked_sub(d.1 as usize)
-        }?;
+        self.try_add(-d, max)
+    }
 
-        if x > max.0 || y > max.1 {
-            return None;
+    #[allow(unused)]
+    /// Steps by `d`, wrapping around the edges of a `dims` sized area so that leaving one side
+    /// re-enters from the opposite one.
+    pub fn wrapping_add(&self, d: Vec2<isize>, dims: Vec2<usize>) -> Vec2<usize> {
+        let wrap =
+            |p: usize, d: isize, len: usize| (p % len + d.rem_euclid(len as isize) as usize) % len;
+        Vec2(wrap(self.0, d.0, dims.0), wrap(self.1, d.1, dims.1))
+    }
+
+    pub fn subtract(&self, d: Vec2<usize>) -> Vec2<isize> {
+        self.signed() - d.signed()
+    }
+
+    fn signed(&self) -> Vec2<isize> {
+        Vec2(self.0 as isize, self.1 as isize)
+    }
+}
+
+impl Vec2<i64> {
+    #[allow(unused)]
+    /// Every integer point on the segment from here to `other`, both ends included. Horizontal,
+    /// vertical and diagonal segments are exact; any other slope follows Bresenham's line.
+    pub fn line_to(&self, other: Self) -> Line {
+        let d = Vec2(
+            other.0.abs_diff(self.0) as i64,
+            other.1.abs_diff(self.1) as i64,
+        );
+        Line {
+            next: Some(*self),
+            end: other,
+            d,
+            step: Vec2((other.0 - self.0).signum(), (other.1 - self.1).signum()),
+            err: d.0 - d.1,
+        }
+    }
+}
+
+/// The points along a segment, see [Vec2::line_to].
+pub struct Line {
+    next: Option<Vec2<i64>>,
+    end: Vec2<i64>,
+    d: Vec2<i64>,
+    step: Vec2<i64>,
+    err: i64,
+}
+
+impl Iterator for Line {
+    type Item = Vec2<i64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let p = self.next?;
+        if p == self.end {
+            self.next = None;
+            return Some(p);
         }
 
-        Some(Vec2(x, y))
+        let mut next = p;
+        let e2 = 2 * self.err;
+        if e2 > -self.d.1 {
+            self.err -= self.d.1;
+            next.0 += self.step.0;
+        }
+        if e2 < self.d.0 {
+            self.err += self.d.0;
+            next.1 += self.step.1;
+        }
+        self.next = Some(next);
+
+        Some(p)
     }
+}
 
-    pub fn subtract(&self, d: Vec2<usize>) -> Vec2<isize> {
-        Vec2(
-            (self.0 as isize) - (d.0 as isize),
-            (self.1 as isize) - (d.1 as isize),
-        )
+macro_rules! distances {
+    ($($i:ty => $u:ty),*) => {
+        $(
+            impl Vec2<$i> {
+                #[allow(unused)]
+                /// The number of edge-sharing steps between the two points.
+                pub fn manhattan(&self, other: &Self) -> $u {
+                    self.0.abs_diff(other.0) + self.1.abs_diff(other.1)
+                }
+
+                #[allow(unused)]
+                /// The number of steps between the two points when diagonal steps are allowed.
+                pub fn chebyshev(&self, other: &Self) -> $u {
+                    self.0.abs_diff(other.0).max(self.1.abs_diff(other.1))
+                }
+            }
+        )*
+    };
+}
+
+distances!(usize => usize, isize => usize, i32 => u32, i64 => u64);
+
+impl<I: Add<Output = I>> Add for Vec2<I> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Vec2(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl<I: Sub<Output = I>> Sub for Vec2<I> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Vec2(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+impl<I: Neg<Output = I>> Neg for Vec2<I> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Vec2(-self.0, -self.1)
+    }
+}
+
+/// Scales both components, e.g. `dir * 3` for three steps in a direction.
+impl<I: Mul<Output = I> + Copy> Mul<I> for Vec2<I> {
+    type Output = Self;
+
+    fn mul(self, rhs: I) -> Self {
+        Vec2(self.0 * rhs, self.1 * rhs)
     }
 }
 
-impl Vec2<isize> {
-    pub fn rotate_clockwise(&self) -> Self {
-        Self(-self.1, self.0)
+impl<I> Vec2<I> {
+    #[allow(unused)]
+    /// Converts to another coordinate type that can hold every value of this one, e.g. `i32` to
+    /// `i64`.
+    pub fn cast<J: From<I>>(self) -> Vec2<J> {
+        Vec2(J::from(self.0), J::from(self.1))
+    }
+
+    /// Converts to another coordinate type, or `None` if either coordinate doesn't fit, e.g. a
+    /// negative `i64` into `usize`.
+    pub fn try_cast<J: TryFrom<I>>(self) -> Option<Vec2<J>> {
+        Some(Vec2(J::try_from(self.0).ok()?, J::try_from(self.1).ok()?))
     }
 }
 
+macro_rules! rotations {
+    ($($i:ty),*) => {
+        $(
+            impl Vec2<$i> {
+                #[allow(unused)]
+                /// Rotates a quarter turn clockwise, with y running down the grid.
+                pub fn rotate_clockwise(&self) -> Self {
+                    Self(-self.1, self.0)
+                }
+
+                #[allow(unused)]
+                /// Rotates a quarter turn anticlockwise, with y running down the grid.
+                pub fn rotate_anticlockwise(&self) -> Self {
+                    Self(self.1, -self.0)
+                }
+            }
+        )*
+    };
+}
+
+rotations!(i32, i64, isize);
+
 impl<I: Debug> Debug for Vec2<I> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({:?}, {:?})", self.0, self.1)
     }
 }
 
+/// Steps to the four cells sharing an edge with a cell: down, up, right and left.
+pub const DIRECTIONS_4: [Vec2<isize>; 4] = [Vec2(0, 1), Vec2(0, -1), Vec2(1, 0), Vec2(-1, 0)];
+
+/// Steps to the eight cells surrounding a cell, clockwise from down.
+pub const DIRECTIONS_8: [Vec2<isize>; 8] = [
+    Vec2(0, 1),
+    Vec2(-1, 1),
+    Vec2(-1, 0),
+    Vec2(-1, -1),
+    Vec2(0, -1),
+    Vec2(1, -1),
+    Vec2(1, 0),
+    Vec2(1, 1),
+];
+
+/// One of the four ways to step to an edge-sharing cell, with up being towards row 0.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    #[allow(unused)]
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn delta(self) -> Vec2<isize> {
+        match self {
+            Self::Up => Vec2(0, -1),
+            Self::Right => Vec2(1, 0),
+            Self::Down => Vec2(0, 1),
+            Self::Left => Vec2(-1, 0),
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn turn_left(self) -> Self {
+        self.turn_right().turn_right().turn_right()
+    }
+}
+
+/// Parses the arrows used for moves and facings, `^`, `>`, `v` and `<`.
+impl TryFrom<char> for Direction {
+    type Error = anyhow::Error;
+
+    fn try_from(c: char) -> anyhow::Result<Self> {
+        match c {
+            '^' => Ok(Self::Up),
+            '>' => Ok(Self::Right),
+            'v' => Ok(Self::Down),
+            '<' => Ok(Self::Left),
+            _ => Err(anyhow!(
+                "direction must be one of '^', '>', 'v' or '<', found {c:?}"
+            )),
+        }
+    }
+}
+
+/// One of the eight ways to step to a surrounding cell, including diagonally.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    #[allow(unused)]
+    pub const ALL: [Self; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    #[allow(unused)]
+    pub fn delta(self) -> Vec2<isize> {
+        match self {
+            Self::Up => Vec2(0, -1),
+            Self::UpRight => Vec2(1, -1),
+            Self::Right => Vec2(1, 0),
+            Self::DownRight => Vec2(1, 1),
+            Self::Down => Vec2(0, 1),
+            Self::DownLeft => Vec2(-1, 1),
+            Self::Left => Vec2(-1, 0),
+            Self::UpLeft => Vec2(-1, -1),
+        }
+    }
+
+    #[allow(unused)]
+    /// Turns an eighth of the way clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    #[allow(unused)]
+    /// Turns an eighth of the way anticlockwise.
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(d: Direction) -> Self {
+        match d {
+            Direction::Up => Self::Up,
+            Direction::Right => Self::Right,
+            Direction::Down => Self::Down,
+            Direction::Left => Self::Left,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Grid2D<T> {
     width: usize,
     height: usize,
@@ -83,12 +360,431 @@ impl<T> Grid2D<T> {
         self.data.get(self.idx(p))
     }
 
+    #[allow(unused)]
+    pub fn get_mut(&mut self, p: Vec2<usize>) -> Option<&mut T> {
+        if p.0 >= self.width || p.1 >= self.height {
+            return None;
+        }
+
+        let idx = self.idx(p);
+        self.data.get_mut(idx)
+    }
+
+    #[allow(unused)]
+    /// Replaces the cell at `p` with `value`, returning the old value, or `None` if `p` is beyond
+    /// the edge of the grid.
+    pub fn set(&mut self, p: Vec2<usize>, value: T) -> Option<T> {
+        self.get_mut(p).map(|cell| std::mem::replace(cell, value))
+    }
+
+    #[allow(unused)]
+    /// The cell at `p`, wrapping around the edges of the grid so any position has one.
+    pub fn get_wrapped(&self, p: Vec2<isize>) -> &T {
+        let p = Vec2(0, 0).wrapping_add(p, Vec2(self.width, self.height));
+        // SAFETY: wrapping_add keeps the position within the grid's width and height.
+        unsafe { self.get_unchecked(p) }
+    }
+
+    #[allow(unused)]
+    /// Whether the signed position `p` lies within the grid.
+    pub fn in_bounds(&self, p: Vec2<isize>) -> bool {
+        (0..self.width as isize).contains(&p.0) && (0..self.height as isize).contains(&p.1)
+    }
+
+    #[allow(unused)]
+    /// The outer ring of cells, row by row.
+    pub fn border(&self) -> impl Iterator<Item = (Vec2<usize>, &T)> {
+        let Vec2(right, bottom) = self.max();
+        (0..self.height)
+            .flat_map(move |y| {
+                let xs: Box<dyn Iterator<Item = usize>> = if y == 0 || y == bottom {
+                    Box::new(0..=right)
+                } else if right == 0 {
+                    Box::new(std::iter::once(0))
+                } else {
+                    Box::new([0, right].into_iter())
+                };
+                xs.map(move |x| Vec2(x, y))
+            })
+            .map(|p| (p, unsafe { self.get_unchecked(p) }))
+    }
+
+    /// The cells sharing an edge with `p`, leaving out any beyond the edge of the grid.
+    pub fn neighbors4(&self, p: Vec2<usize>) -> impl Iterator<Item = (Vec2<usize>, &T)> {
+        self.neighbors(p, &DIRECTIONS_4)
+    }
+
+    #[allow(unused)]
+    /// The cells surrounding `p`, including diagonally, leaving out any beyond the edge of the
+    /// grid.
+    pub fn neighbors8(&self, p: Vec2<usize>) -> impl Iterator<Item = (Vec2<usize>, &T)> {
+        self.neighbors(p, &DIRECTIONS_8)
+    }
+
+    fn neighbors<'a>(
+        &'a self,
+        p: Vec2<usize>,
+        directions: &'static [Vec2<isize>],
+    ) -> impl Iterator<Item = (Vec2<usize>, &'a T)> {
+        directions.iter().filter_map(move |d| {
+            let p = p.try_add(*d, self.max())?;
+            Some((p, self.get(p)?))
+        })
+    }
+
+    /// A grid of the same dimensions with each cell converted by `f`, e.g. a map into walls.
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid2D<U> {
+        Grid2D {
+            width: self.width,
+            height: self.height,
+            data: self.data.iter().map(f).collect(),
+        }
+    }
+
+    /// Breadth-first search from `start`, stepping to whichever positions `neighbors` returns for
+    /// each visited cell. Positions beyond the edge of the grid are ignored.
+    pub fn bfs<F, N>(&self, start: Vec2<usize>, mut neighbors: F) -> Bfs
+    where
+        F: FnMut(Vec2<usize>, &T) -> N,
+        N: IntoIterator<Item = Vec2<usize>>,
+    {
+        let mut distances = self.map(|_| None);
+        let mut order = vec![];
+        let mut queue = VecDeque::new();
+
+        if let Some(d) = distances.get_mut(start) {
+            *d = Some(0);
+            queue.push_back((start, 0));
+        }
+
+        while let Some((p, distance)) = queue.pop_front() {
+            order.push(p);
+            // SAFETY: only positions with a distance, so within the grid, are queued.
+            let cell = unsafe { self.get_unchecked(p) };
+            for next in neighbors(p, cell) {
+                if let Some(d @ None) = distances.get_mut(next) {
+                    *d = Some(distance + 1);
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        Bfs { order, distances }
+    }
+
+    #[allow(unused)]
+    /// The connected region containing `p`: every cell reachable from it through edge-sharing
+    /// cells equal to it. Empty if `p` is beyond the edge of the grid.
+    pub fn flood_fill(&self, p: Vec2<usize>) -> Region
+    where
+        T: PartialEq,
+    {
+        if self.get(p).is_none() {
+            return Region::default();
+        }
+
+        self.region(p, &mut |a, b| a == b)
+    }
+
+    #[allow(unused)]
+    /// Splits the grid into disjoint connected regions, where two edge-sharing cells belong to the
+    /// same region if `same` holds for them. Regions are in order of their first cell, row by row.
+    pub fn regions<F: FnMut(&T, &T) -> bool>(&self, mut same: F) -> Vec<Region> {
+        let mut seen = gxhash::HashSet::default();
+        let mut regions = vec![];
+
+        for (p, _) in self.iter() {
+            if seen.contains(&p) {
+                continue;
+            }
+
+            let region = self.region(p, &mut same);
+            seen.extend(region.iter().copied());
+            regions.push(region);
+        }
+
+        regions
+    }
+
+    #[allow(unused)]
+    /// Numbers each connected region, as [Grid2D::regions] finds them, returning a grid of the
+    /// region each cell belongs to along with the size and extent of each region.
+    pub fn label_regions<F: FnMut(&T, &T) -> bool>(&self, same: F) -> RegionLabels {
+        let mut labels = self.map(|_| 0);
+        let regions = self
+            .regions(same)
+            .into_iter()
+            .enumerate()
+            .map(|(label, region)| {
+                for p in region.iter() {
+                    labels[*p] = label as u32;
+                }
+                RegionSummary {
+                    size: region.area(),
+                    bounds: BoundingBox::from_points(
+                        region.iter().filter_map(|p| p.try_cast::<i64>()),
+                    )
+                    .expect("regions to have at least one cell"),
+                }
+            })
+            .collect();
+
+        RegionLabels { labels, regions }
+    }
+
+    fn region<F: FnMut(&T, &T) -> bool>(&self, p: Vec2<usize>, same: &mut F) -> Region {
+        let cells = self
+            .bfs(p, |p, a| {
+                self.neighbors4(p)
+                    .filter(|(_, b)| same(a, b))
+                    .map(|(p, _)| p)
+                    .collect::<Vec<_>>()
+            })
+            .order
+            .into_iter()
+            .collect();
+        Region { cells }
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = (Vec2<usize>, &T)> {
         (0..self.height)
             .flat_map(|y| (0..self.width).map(move |x| Vec2(x, y)))
             .map(|p| (p, unsafe { self.get_unchecked(p) }))
     }
 
+    #[allow(unused)]
+    /// The position of the first cell, row by row, matching `predicate`.
+    pub fn find<P: FnMut(&T) -> bool>(&self, mut predicate: P) -> Option<Vec2<usize>> {
+        self.positions_of(&mut predicate).next()
+    }
+
+    /// The positions of every cell matching `predicate`, row by row.
+    pub fn positions_of<'a, P: FnMut(&T) -> bool + 'a>(
+        &'a self,
+        mut predicate: P,
+    ) -> impl Iterator<Item = Vec2<usize>> + 'a {
+        self.iter()
+            .filter_map(move |(p, cell)| predicate(cell).then_some(p))
+    }
+
+    #[allow(unused)]
+    /// The cells that differ from those at the same position in `other`, row by row, as the
+    /// position, this grid's cell and the other's. Cells beyond the edge of either grid are
+    /// skipped.
+    pub fn diff<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = (Vec2<usize>, &'a T, &'a T)>
+    where
+        T: PartialEq,
+    {
+        self.iter().filter_map(|(p, a)| {
+            let b = other.get(p)?;
+            (a != b).then_some((p, a, b))
+        })
+    }
+
+    /// Every `size` sub-view of the grid that fits entirely inside it, row by row by top-left
+    /// corner. Yields nothing if `size` is larger than the grid.
+    pub fn windows(&self, size: Vec2<usize>) -> impl Iterator<Item = Window<'_, T>> {
+        let xs = (self.width + 1).saturating_sub(size.0);
+        let ys = (self.height + 1).saturating_sub(size.1);
+        (0..ys)
+            .flat_map(move |y| (0..xs).map(move |x| Vec2(x, y)))
+            .map(move |origin| Window {
+                grid: self,
+                origin,
+                size,
+            })
+    }
+
+    #[allow(unused)]
+    /// Renders the grid row by row, drawing each cell as the character `f` gives for it.
+    pub fn render_with<F: FnMut(Vec2<usize>, &T) -> char>(&self, mut f: F) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for (p, cell) in self.iter() {
+            if p.0 == 0 && p.1 > 0 {
+                out.push('\n');
+            }
+            out.push(f(p, cell));
+        }
+        out
+    }
+
+    #[allow(unused)]
+    /// Renders the grid like [Display], but with `mark` drawn over each of `positions`, e.g. to
+    /// compare a path against a puzzle's illustration.
+    pub fn render_highlight<P>(&self, positions: P, mark: char) -> String
+    where
+        T: Display,
+        P: IntoIterator<Item = Vec2<usize>>,
+    {
+        let mut marked = self.map(|_| false);
+        for p in positions {
+            marked.set(p, true);
+        }
+
+        let mut out = String::new();
+        for (p, cell) in self.iter() {
+            if p.0 == 0 && p.1 > 0 {
+                out.push('\n');
+            }
+            if marked[p] {
+                out.push(mark);
+            } else {
+                out.push_str(&cell.to_string());
+            }
+        }
+        out
+    }
+
+    #[allow(unused)]
+    /// Swaps the cells at `a` and `b`.
+    ///
+    /// Panics if either is beyond the edge of the grid, like indexing.
+    pub fn swap(&mut self, a: Vec2<usize>, b: Vec2<usize>) {
+        for p in [a, b] {
+            assert!(
+                p.0 < self.width && p.1 < self.height,
+                "{p:?} is beyond the edge of the grid"
+            );
+        }
+        let (a, b) = (self.idx(a), self.idx(b));
+        self.data.swap(a, b);
+    }
+
+    #[allow(unused)]
+    /// Swaps the cells at `from` and `to` if `accept` holds for the cell at `to`, e.g. to move a
+    /// box into an empty space. Returns whether they were swapped, which they aren't if either is
+    /// beyond the edge of the grid.
+    pub fn swap_if<F: FnOnce(&T) -> bool>(
+        &mut self,
+        from: Vec2<usize>,
+        to: Vec2<usize>,
+        accept: F,
+    ) -> bool {
+        if self.get(from).is_none() || !self.get(to).is_some_and(accept) {
+            return false;
+        }
+
+        self.swap(from, to);
+        true
+    }
+
+    #[allow(unused)]
+    /// The cell at `p` for updating in place, or `None` if `p` is beyond the edge of the grid.
+    pub fn entry(&mut self, p: Vec2<usize>) -> Option<Entry<'_, T>> {
+        let cell = self.get_mut(p)?;
+        Some(Entry { position: p, cell })
+    }
+
+    #[cfg(feature = "rayon")]
+    #[allow(unused)]
+    /// Like [Grid2D::iter], but in parallel.
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = (Vec2<usize>, &T)>
+    where
+        T: Sync,
+    {
+        let width = self.width;
+        self.data
+            .par_iter()
+            .enumerate()
+            .map(move |(i, cell)| (Vec2(i % width, i / width), cell))
+    }
+
+    #[cfg(feature = "rayon")]
+    #[allow(unused)]
+    /// Like [Grid2D::positions_of], but tests the cells in parallel.
+    pub fn par_positions_of<'a, P>(
+        &'a self,
+        predicate: P,
+    ) -> impl ParallelIterator<Item = Vec2<usize>> + 'a
+    where
+        T: Sync,
+        P: Fn(&T) -> bool + Sync + Send + 'a,
+    {
+        self.par_iter()
+            .filter_map(move |(p, cell)| predicate(cell).then_some(p))
+    }
+
+    /// Each row as a slice, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.data.chunks(self.width.max(1))
+    }
+
+    /// Every cell, column by column rather than row by row.
+    pub fn iter_column_major(&self) -> impl Iterator<Item = (Vec2<usize>, &T)> {
+        (0..self.width)
+            .flat_map(|x| (0..self.height).map(move |y| Vec2(x, y)))
+            .map(|p| (p, unsafe { self.get_unchecked(p) }))
+    }
+
+    /// Each diagonal running down and to the right, from the one starting in the bottom left
+    /// corner to the one in the top right.
+    pub fn iter_diagonals(
+        &self,
+    ) -> impl Iterator<Item = impl Iterator<Item = (Vec2<usize>, &T)> + '_> + '_ {
+        let starts = (0..self.height)
+            .rev()
+            .map(|y| Vec2(0, y))
+            .chain((1..self.width).map(|x| Vec2(x, 0)));
+        starts.map(|start| self.line(start, Vec2(1, 1)))
+    }
+
+    /// Each diagonal running down and to the left, from the one starting in the top left corner
+    /// to the one in the bottom right.
+    pub fn iter_anti_diagonals(
+        &self,
+    ) -> impl Iterator<Item = impl Iterator<Item = (Vec2<usize>, &T)> + '_> + '_ {
+        let starts = (0..self.width)
+            .map(|x| Vec2(x, 0))
+            .chain((1..self.height).map(|y| Vec2(self.width - 1, y)));
+        starts.map(|start| self.line(start, Vec2(-1, 1)))
+    }
+
+    /// The cells from `start` stepping by `d` until the edge of the grid.
+    fn line(
+        &self,
+        start: Vec2<usize>,
+        d: Vec2<isize>,
+    ) -> impl Iterator<Item = (Vec2<usize>, &T)> + '_ {
+        std::iter::successors(self.get(start).map(|_| start), move |p| {
+            p.try_add(d, self.max())
+        })
+        .map(|p| (p, unsafe { self.get_unchecked(p) }))
+    }
+
+    #[allow(unused)]
+    /// Packs `p` into a single small integer, its index in the grid, which is cheaper to hash or
+    /// use as an index than the position itself. See [Grid2D::unpack].
+    pub fn pack(&self, p: Vec2<usize>) -> u32 {
+        self.idx(p) as u32
+    }
+
+    #[allow(unused)]
+    /// The position [Grid2D::pack] packed into `i`.
+    pub fn unpack(&self, i: u32) -> Vec2<usize> {
+        let i = i as usize;
+        Vec2(i % self.width, i / self.width)
+    }
+
+    #[allow(unused)]
+    /// An empty set of positions in this grid, stored as one bit per cell.
+    pub fn position_set(&self) -> PositionSet {
+        PositionSet {
+            width: self.width,
+            height: self.height,
+            words: vec![0; (self.data.len()).div_ceil(64)],
+            len: 0,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Vec2<usize>, &mut T)> {
+        let width = self.width;
+        self.data
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, cell)| (Vec2(i % width, i / width), cell))
+    }
+
     unsafe fn get_unchecked(&self, p: Vec2<usize>) -> &T {
         self.data.get_unchecked(self.idx(p))
     }
@@ -98,59 +794,1151 @@ impl<T> Grid2D<T> {
     }
 }
 
-impl<I: Iterator<Item = char>> From<I> for Grid2D<char> {
-    fn from(value: I) -> Self {
-        let mut width = 0;
-        let mut height = 1;
-        let mut data = vec![];
+/// The result of [Grid2D::bfs].
+pub struct Bfs {
+    /// Every position reached, in the order they were visited.
+    pub order: Vec<Vec2<usize>>,
+    /// The number of steps from the start to each position, or `None` if it was not reached.
+    pub distances: Grid2D<Option<usize>>,
+}
 
-        for c in value {
-            match c {
-                '\n' => {
-                    height += 1;
-                }
-                _ => {
-                    if height == 1 {
-                        width += 1;
-                    }
-                    data.push(c);
-                }
-            }
-        }
+impl Bfs {
+    pub fn distance(&self, p: Vec2<usize>) -> Option<usize> {
+        self.distances.get(p).copied().flatten()
+    }
+}
 
-        Self {
-            width,
-            height,
-            data,
+/// A set of positions within a [Grid2D], as a bitset with one bit per cell, for visited sets on
+/// hot paths. See [Grid2D::position_set].
+#[derive(Clone)]
+pub struct PositionSet {
+    width: usize,
+    height: usize,
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl PositionSet {
+    #[allow(unused)]
+    /// Adds `p` to the set, returning whether it was newly added.
+    ///
+    /// Panics if `p` is beyond the edge of the grid.
+    pub fn insert(&mut self, p: Vec2<usize>) -> bool {
+        let (word, bit) = self.bit(p);
+        let added = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        self.len += usize::from(added);
+        added
+    }
+
+    #[allow(unused)]
+    pub fn contains(&self, p: Vec2<usize>) -> bool {
+        if p.0 >= self.width || p.1 >= self.height {
+            return false;
         }
+
+        let (word, bit) = self.bit(p);
+        self.words[word] & bit != 0
+    }
+
+    #[allow(unused)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[allow(unused)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[allow(unused)]
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+        self.len = 0;
+    }
+
+    #[allow(unused)]
+    /// The positions in the set, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = Vec2<usize>> + '_ {
+        self.words.iter().enumerate().flat_map(move |(w, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let i = w * 64 + word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(Vec2(i % self.width, i / self.width))
+            })
+        })
+    }
+
+    fn bit(&self, p: Vec2<usize>) -> (usize, u64) {
+        assert!(
+            p.0 < self.width && p.1 < self.height,
+            "{p:?} is beyond the edge of the grid"
+        );
+        let i = p.1 * self.width + p.0;
+        (i / 64, 1 << (i % 64))
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+/// A single cell of a [Grid2D], see [Grid2D::entry].
+pub struct Entry<'a, T> {
+    position: Vec2<usize>,
+    cell: &'a mut T,
+}
 
-    #[test]
-    fn grid_get() {
-        let grid = Grid2D::from("abcd\nefgh\nijkl".chars());
+impl<'a, T> Entry<'a, T> {
+    #[allow(unused)]
+    pub fn position(&self) -> Vec2<usize> {
+        self.position
+    }
 
-        assert_eq!(grid.get(Vec2(0, 0)), Some(&'a'));
-        assert_eq!(grid.get(Vec2(3, 1)), Some(&'h'));
-        assert_eq!(grid.get(Vec2(1, 2)), Some(&'j'));
-        assert_eq!(grid.get(Vec2(1, 4)), None);
-        assert_eq!(grid.get(Vec2(4, 1)), None);
+    #[allow(unused)]
+    pub fn get(&self) -> &T {
+        self.cell
     }
 
-    #[test]
-    fn grid_iter() {
-        let grid = Grid2D::from("abcd\nefgh\nijkl".chars());
+    #[allow(unused)]
+    pub fn into_mut(self) -> &'a mut T {
+        self.cell
+    }
 
-        let mut iter = grid.iter();
+    #[allow(unused)]
+    pub fn and_modify<F: FnOnce(&mut T)>(self, f: F) -> Self {
+        f(self.cell);
+        self
+    }
 
-        assert_eq!(iter.next(), Some((Vec2(0, 0), &'a')));
-        assert_eq!(iter.next(), Some((Vec2(1, 0), &'b')));
-        assert_eq!(iter.next(), Some((Vec2(2, 0), &'c')));
-        assert_eq!(iter.next(), Some((Vec2(3, 0), &'d')));
-        assert_eq!(iter.next(), Some((Vec2(0, 1), &'e')));
+    #[allow(unused)]
+    /// Replaces the cell with `value` if `accept` holds for it, returning the old value.
+    pub fn replace_if<F: FnOnce(&T) -> bool>(self, accept: F, value: T) -> Option<T> {
+        accept(self.cell).then(|| std::mem::replace(self.cell, value))
+    }
+}
+
+/// A rectangular view into a [Grid2D], indexed relative to its top-left corner. See
+/// [Grid2D::windows].
+pub struct Window<'a, T> {
+    grid: &'a Grid2D<T>,
+    origin: Vec2<usize>,
+    size: Vec2<usize>,
+}
+
+impl<'a, T> Window<'a, T> {
+    #[allow(unused)]
+    /// The position of the window's top-left corner in the grid.
+    pub fn origin(&self) -> Vec2<usize> {
+        self.origin
+    }
+
+    /// The cell at `p` relative to the window's top-left corner, or `None` if `p` is outside the
+    /// window.
+    pub fn get(&self, p: Vec2<usize>) -> Option<&'a T> {
+        if p.0 >= self.size.0 || p.1 >= self.size.1 {
+            return None;
+        }
+
+        self.grid
+            .get(Vec2(self.origin.0 + p.0, self.origin.1 + p.1))
+    }
+}
+
+/// Like [Grid2D::get], but panics if `p` is beyond the edge of the grid.
+impl<T> Index<Vec2<usize>> for Grid2D<T> {
+    type Output = T;
+
+    fn index(&self, p: Vec2<usize>) -> &T {
+        self.get(p)
+            .unwrap_or_else(|| panic!("{p:?} is beyond the edge of the grid"))
+    }
+}
+
+/// Like [Grid2D::get_mut], but panics if `p` is beyond the edge of the grid.
+impl<T> IndexMut<Vec2<usize>> for Grid2D<T> {
+    fn index_mut(&mut self, p: Vec2<usize>) -> &mut T {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{p:?} is beyond the edge of the grid"))
+    }
+}
+
+impl<T> Index<Vec2<usize>> for Window<'_, T> {
+    type Output = T;
+
+    fn index(&self, p: Vec2<usize>) -> &T {
+        self.get(p).expect("position to be inside the window")
+    }
+}
+
+impl<T> Grid2D<T> {
+    #[allow(unused)]
+    /// A `width` by `height` grid with every cell set to `fill`, e.g. a visited map.
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            data: vec![fill; width * height],
+        }
+    }
+
+    #[allow(unused)]
+    /// Builds a grid from its rows, top to bottom.
+    ///
+    /// Returns an error if the rows are not all the same width.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> anyhow::Result<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        if let Some((y, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != width) {
+            return Err(anyhow!(
+                "grid rows must all be {width} wide, but row {y} is {}",
+                row.len()
+            ));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            data: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Builds a grid from rows of characters separated by `'\n'`, converting each character into
+    /// a cell with `f`, e.g. digits into heights or symbols into tiles. A single trailing newline
+    /// is ignored. Returns an error if the rows are not all the same width.
+    pub fn from_chars_map<I, F>(value: I, mut f: F) -> anyhow::Result<Self>
+    where
+        I: IntoIterator<Item = char>,
+        F: FnMut(char) -> T,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut row_width = 0;
+        let mut data = vec![];
+        // A row only ends once something follows its newline, so a trailing newline at the end of
+        // the input doesn't add an empty row.
+        let mut newline = false;
+
+        let mut end_row = |row_width: usize| match width {
+            Some(width) if width != row_width => Err(anyhow!(
+                "grid rows must all be {width} wide, but row {height} is {row_width}"
+            )),
+            _ => {
+                width = Some(row_width);
+                height += 1;
+                Ok(())
+            }
+        };
+
+        for c in value {
+            if newline {
+                end_row(row_width)?;
+                row_width = 0;
+                newline = false;
+            }
+            match c {
+                '\n' => newline = true,
+                _ => {
+                    row_width += 1;
+                    data.push(f(c));
+                }
+            }
+        }
+        end_row(row_width)?;
+
+        Ok(Self {
+            width: width.unwrap_or_default(),
+            height,
+            data,
+        })
+    }
+}
+
+/// Like [Grid2D::from_chars_map] without converting the characters, but panics if the rows are not
+/// all the same width.
+impl<I: Iterator<Item = char>> From<I> for Grid2D<char> {
+    fn from(value: I) -> Self {
+        Self::from_chars_map(value, |c| c).unwrap_or_else(|err| panic!("{err}"))
+    }
+}
+
+/// Renders the grid row by row, one line per row, so it can be eyeballed while debugging. For a
+/// `Grid2D<char>` this is the puzzle input format, which parses back into the same grid.
+impl<T: Display> Display for Grid2D<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.data.chunks(self.width.max(1)).enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A connected set of cells, see [Grid2D::regions].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Region {
+    cells: gxhash::HashSet<Vec2<usize>>,
+}
+
+impl Deref for Region {
+    type Target = gxhash::HashSet<Vec2<usize>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cells
+    }
+}
+
+impl Region {
+    #[allow(unused)]
+    /// The number of cells in the region.
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[allow(unused)]
+    /// The number of cell edges on the boundary of the region, including around any holes.
+    pub fn perimeter(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|p| {
+                DIRECTIONS_4
+                    .iter()
+                    .filter(|d| !self.has(p.signed() + **d))
+                    .count()
+            })
+            .sum()
+    }
+
+    #[allow(unused)]
+    /// The number of straight sides on the boundary of the region, including around any holes,
+    /// found by counting its corners since every side runs from one corner to the next.
+    pub fn count_sides(&self) -> usize {
+        let corners = [
+            (Vec2(0, -1), Vec2(1, 0)),
+            (Vec2(1, 0), Vec2(0, 1)),
+            (Vec2(0, 1), Vec2(-1, 0)),
+            (Vec2(-1, 0), Vec2(0, -1)),
+        ];
+
+        self.cells
+            .iter()
+            .map(|p| {
+                let p = p.signed();
+                corners
+                    .iter()
+                    .filter(|(a, b)| {
+                        let (a, b, diagonal) =
+                            (self.has(p + *a), self.has(p + *b), self.has(p + *a + *b));
+                        // Either the region turns outwards here, or it turns inwards around a
+                        // cell missing from the inside of a bend.
+                        (!a && !b) || (a && b && !diagonal)
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    fn has(&self, p: Vec2<isize>) -> bool {
+        match (usize::try_from(p.0), usize::try_from(p.1)) {
+            (Ok(x), Ok(y)) => self.cells.contains(&Vec2(x, y)),
+            _ => false,
+        }
+    }
+}
+
+/// The result of [Grid2D::label_regions].
+pub struct RegionLabels {
+    /// The label of the region each cell belongs to, which indexes `regions`.
+    pub labels: Grid2D<u32>,
+    pub regions: Vec<RegionSummary>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RegionSummary {
+    /// The number of cells in the region.
+    pub size: usize,
+    pub bounds: BoundingBox,
+}
+
+/// The corners of an axis-aligned box, both inclusive.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub min: Vec2<i64>,
+    pub max: Vec2<i64>,
+}
+
+impl BoundingBox {
+    #[allow(unused)]
+    /// The smallest box containing every point, or `None` if there are none.
+    pub fn from_points<I: IntoIterator<Item = Vec2<i64>>>(points: I) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = Self {
+            min: first,
+            max: first,
+        };
+        for p in points {
+            bounds.include(p);
+        }
+        Some(bounds)
+    }
+
+    #[allow(unused)]
+    pub fn contains(&self, p: Vec2<i64>) -> bool {
+        (self.min.0..=self.max.0).contains(&p.0) && (self.min.1..=self.max.1).contains(&p.1)
+    }
+
+    #[allow(unused)]
+    pub fn width(&self) -> u64 {
+        self.max.0.abs_diff(self.min.0) + 1
+    }
+
+    #[allow(unused)]
+    pub fn height(&self) -> u64 {
+        self.max.1.abs_diff(self.min.1) + 1
+    }
+
+    #[allow(unused)]
+    /// The number of integer points inside the box.
+    pub fn area(&self) -> u64 {
+        self.width() * self.height()
+    }
+
+    #[allow(unused)]
+    /// Grows the box just enough to contain `p`.
+    pub fn include(&mut self, p: Vec2<i64>) {
+        self.min = Vec2(self.min.0.min(p.0), self.min.1.min(p.1));
+        self.max = Vec2(self.max.0.max(p.0), self.max.1.max(p.1));
+    }
+
+    #[allow(unused)]
+    /// The box grown by `margin` on every side, e.g. to leave a border around a drawing.
+    pub fn expand(&self, margin: i64) -> Self {
+        Self {
+            min: self.min - Vec2(margin, margin),
+            max: self.max + Vec2(margin, margin),
+        }
+    }
+}
+
+/// A grid without edges, keyed by signed coordinates, for when positions can go negative or the
+/// area is unbounded. Only the cells that have been set take up space.
+#[derive(Default)]
+pub struct SparseGrid<T> {
+    cells: gxhash::HashMap<Vec2<i64>, T>,
+}
+
+impl<T> SparseGrid<T> {
+    #[allow(unused)]
+    pub fn new() -> Self {
+        Self {
+            cells: gxhash::HashMap::default(),
+        }
+    }
+
+    #[allow(unused)]
+    pub fn get(&self, p: Vec2<i64>) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    #[allow(unused)]
+    pub fn get_mut(&mut self, p: Vec2<i64>) -> Option<&mut T> {
+        self.cells.get_mut(&p)
+    }
+
+    #[allow(unused)]
+    /// Sets the cell at `p` to `value`, returning the old value if it had been set.
+    pub fn set(&mut self, p: Vec2<i64>, value: T) -> Option<T> {
+        self.cells.insert(p, value)
+    }
+
+    #[allow(unused)]
+    pub fn remove(&mut self, p: Vec2<i64>) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    #[allow(unused)]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[allow(unused)]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    #[allow(unused)]
+    /// The set cells sharing an edge with `p`.
+    pub fn neighbors4(&self, p: Vec2<i64>) -> impl Iterator<Item = (Vec2<i64>, &T)> {
+        self.neighbors(p, &DIRECTIONS_4)
+    }
+
+    #[allow(unused)]
+    /// The set cells surrounding `p`, including diagonally.
+    pub fn neighbors8(&self, p: Vec2<i64>) -> impl Iterator<Item = (Vec2<i64>, &T)> {
+        self.neighbors(p, &DIRECTIONS_8)
+    }
+
+    fn neighbors<'a>(
+        &'a self,
+        p: Vec2<i64>,
+        directions: &'static [Vec2<isize>],
+    ) -> impl Iterator<Item = (Vec2<i64>, &'a T)> {
+        directions.iter().filter_map(move |d| {
+            let p = p + d.try_cast()?;
+            Some((p, self.get(p)?))
+        })
+    }
+
+    #[allow(unused)]
+    /// The smallest box around every set cell, or `None` if the grid is empty.
+    pub fn bounds(&self) -> Option<BoundingBox> {
+        BoundingBox::from_points(self.cells.keys().copied())
+    }
+
+    #[allow(unused)]
+    /// The set cells, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2<i64>, &T)> {
+        self.cells.iter().map(|(p, cell)| (*p, cell))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn vec2_ops() {
+        let p = Vec2(3_isize, -2);
+        let d = Vec2(1, 2);
+
+        assert_eq!(p + d * 3, Vec2(6, 4));
+        assert_eq!(p - d, Vec2(2, -4));
+        assert_eq!(-d, Vec2(-1, -2));
+        assert_eq!(Vec2(5_i64, 7) * -2, Vec2(-10, -14));
+    }
+
+    #[test]
+    fn vec2_distances() {
+        assert_eq!(Vec2(1_usize, 5).manhattan(&Vec2(4, 2)), 6);
+        assert_eq!(Vec2(1_usize, 5).chebyshev(&Vec2(4, 2)), 3);
+        assert_eq!(Vec2(-2_isize, 3).manhattan(&Vec2(2, -1)), 8);
+        assert_eq!(Vec2(-2_i64, 3).chebyshev(&Vec2(2, -4)), 7);
+        assert_eq!(Vec2(7_i64, 7).manhattan(&Vec2(7, 7)), 0);
+    }
+
+    #[test]
+    fn vec2_line_to() {
+        let line: Vec<_> = Vec2(1, 1).line_to(Vec2(1, 4)).collect();
+        assert_eq!(line, [Vec2(1, 1), Vec2(1, 2), Vec2(1, 3), Vec2(1, 4)]);
+
+        let line: Vec<_> = Vec2(3, -1).line_to(Vec2(0, 2)).collect();
+        assert_eq!(line, [Vec2(3, -1), Vec2(2, 0), Vec2(1, 1), Vec2(0, 2)]);
+
+        let line: Vec<_> = Vec2(0, 0).line_to(Vec2(4, 2)).collect();
+        assert_eq!(
+            line,
+            [Vec2(0, 0), Vec2(1, 0), Vec2(2, 1), Vec2(3, 1), Vec2(4, 2)]
+        );
+
+        assert_eq!(
+            Vec2(5, 5).line_to(Vec2(5, 5)).collect::<Vec<_>>(),
+            [Vec2(5, 5)]
+        );
+    }
+
+    #[test]
+    fn vec2_wrapping_add() {
+        let dims = Vec2(11, 7);
+
+        assert_eq!(Vec2(2, 4).wrapping_add(Vec2(2, -3), dims), Vec2(4, 1));
+        assert_eq!(Vec2(4, 1).wrapping_add(Vec2(2, -3), dims), Vec2(6, 5));
+        assert_eq!(Vec2(10, 6).wrapping_add(Vec2(1, 1), dims), Vec2(0, 0));
+        assert_eq!(Vec2(2, 4).wrapping_add(Vec2(2, -3) * 5, dims), Vec2(1, 3));
+        assert_eq!(
+            Vec2(0, 0).wrapping_add(Vec2(isize::MIN, isize::MAX), dims),
+            Vec2(
+                isize::MIN.rem_euclid(11) as usize,
+                isize::MAX.rem_euclid(7) as usize
+            )
+        );
+    }
+
+    #[test]
+    fn vec2_casts() {
+        assert_eq!(Vec2(3_i32, -4).cast::<i64>(), Vec2(3_i64, -4));
+        assert_eq!(Vec2(3_i64, 4).try_cast::<usize>(), Some(Vec2(3_usize, 4)));
+        assert_eq!(Vec2(3_i64, -4).try_cast::<usize>(), None);
+        assert_eq!(Vec2(u64::MAX, 0).try_cast::<i64>(), None);
+    }
+
+    #[test]
+    fn vec2_rotations() {
+        assert_eq!(Vec2(0_i64, -1).rotate_clockwise(), Vec2(1, 0));
+        assert_eq!(Vec2(0_i32, -1).rotate_anticlockwise(), Vec2(-1, 0));
+        assert_eq!(
+            Vec2(2_isize, 5).rotate_clockwise().rotate_anticlockwise(),
+            Vec2(2, 5)
+        );
+        assert_eq!(Vec2(-3_i32, 4).manhattan(&Vec2(0, 0)), 7_u32);
+    }
+
+    #[test]
+    fn vec2_checked() {
+        let max = Vec2(4, 4);
+
+        assert_eq!(Vec2(1, 1).try_add(Vec2(2, -1), max), Some(Vec2(3, 0)));
+        assert_eq!(Vec2(1, 1).try_add(Vec2(-2, 0), max), None);
+        assert_eq!(Vec2(1, 1).try_add(Vec2(0, 4), max), None);
+        assert_eq!(Vec2(1, 1).try_subtract(Vec2(-3, 1), max), Some(Vec2(4, 0)));
+        assert_eq!(Vec2(1, 3).subtract(Vec2(2, 1)), Vec2(-1, 2));
+    }
+
+    #[test]
+    fn direction_turns() {
+        let d = Direction::try_from('^').expect("'^' to parse");
+
+        assert_eq!(d.delta(), Vec2(0, -1));
+        assert_eq!(d.turn_right(), Direction::Right);
+        assert_eq!(d.turn_left(), Direction::Left);
+        assert_eq!(
+            Direction::try_from('<').map(Direction::delta).ok(),
+            Some(Vec2(-1, 0))
+        );
+        assert!(Direction::try_from('x').is_err());
+
+        for d in Direction::ALL {
+            assert_eq!(d.delta().rotate_clockwise(), d.turn_right().delta());
+            assert_eq!(Direction8::from(d).delta(), d.delta());
+        }
+    }
+
+    #[test]
+    fn direction8_turns() {
+        assert_eq!(Direction8::Up.turn_left(), Direction8::UpLeft);
+        assert_eq!(Direction8::UpLeft.turn_right(), Direction8::Up);
+        assert_eq!(Direction8::DownRight.delta(), Vec2(1, 1));
+
+        let deltas: Vec<_> = Direction8::ALL.iter().map(|d| d.delta()).collect();
+        for d in DIRECTIONS_8 {
+            assert!(deltas.contains(&d));
+        }
+    }
+
+    #[test]
+    fn grid_new() {
+        let mut grid = Grid2D::new(3, 2, false);
+        grid.set(Vec2(2, 1), true);
+
+        assert_eq!(grid.max(), Vec2(2, 1));
+        assert_eq!(grid.positions_of(|&v| v).collect::<Vec<_>>(), [Vec2(2, 1)]);
+    }
+
+    #[test]
+    fn grid_from_rows() {
+        let grid = Grid2D::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).expect("grid to build");
+        assert_eq!(grid.max(), Vec2(2, 1));
+        assert_eq!(grid.get(Vec2(0, 1)), Some(&4));
+
+        let err = Grid2D::from_rows(vec![vec![1, 2], vec![3]]).map(|_| ());
+        assert_eq!(
+            err.map_err(|err| err.to_string()),
+            Err("grid rows must all be 2 wide, but row 1 is 1".to_string())
+        );
+    }
+
+    #[test]
+    fn grid_get() {
+        let grid = Grid2D::from("abcd\nefgh\nijkl".chars());
+
+        assert_eq!(grid.get(Vec2(0, 0)), Some(&'a'));
+        assert_eq!(grid.get(Vec2(3, 1)), Some(&'h'));
+        assert_eq!(grid.get(Vec2(1, 2)), Some(&'j'));
+        assert_eq!(grid.get(Vec2(1, 4)), None);
+        assert_eq!(grid.get(Vec2(4, 1)), None);
+    }
+
+    #[test]
+    fn grid_index() {
+        let mut grid = Grid2D::from("abcd\nefgh\nijkl".chars());
+
+        assert_eq!(grid[Vec2(3, 1)], 'h');
+        grid[Vec2(3, 1)] = '#';
+        assert_eq!(grid.get(Vec2(3, 1)), Some(&'#'));
+
+        let out_of_bounds = std::panic::catch_unwind(|| grid[Vec2(4, 1)]);
+        assert!(out_of_bounds.is_err());
+    }
+
+    #[test]
+    fn grid_swap() {
+        let mut grid = Grid2D::from("@O.#".chars());
+
+        grid.swap(Vec2(0, 0), Vec2(3, 0));
+        assert_eq!(grid.to_string(), "#O.@");
+
+        assert!(grid.swap_if(Vec2(1, 0), Vec2(2, 0), |&c| c == '.'));
+        assert_eq!(grid.to_string(), "#.O@");
+        assert!(!grid.swap_if(Vec2(2, 0), Vec2(3, 0), |&c| c == '.'));
+        assert!(!grid.swap_if(Vec2(3, 0), Vec2(4, 0), |_| true));
+        assert_eq!(grid.to_string(), "#.O@");
+    }
+
+    #[test]
+    fn grid_entry() {
+        let mut grid = Grid2D::from("12\n34".chars()).map(|c| c.to_digit(10).unwrap());
+
+        let entry = grid.entry(Vec2(1, 1)).expect("cell to be in the grid");
+        assert_eq!(entry.position(), Vec2(1, 1));
+        assert_eq!(*entry.and_modify(|n| *n *= 10).get(), 40);
+
+        assert_eq!(
+            grid.entry(Vec2(0, 0)).unwrap().replace_if(|&n| n > 5, 0),
+            None
+        );
+        assert_eq!(
+            grid.entry(Vec2(1, 1)).unwrap().replace_if(|&n| n > 5, 0),
+            Some(40)
+        );
+        *grid.entry(Vec2(1, 0)).unwrap().into_mut() += 1;
+        assert_eq!(grid.to_string(), "13\n30");
+        assert!(grid.entry(Vec2(2, 0)).is_none());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn grid_par_iter() {
+        let grid = Grid2D::from("ab^d\ne^gh\nijk^".chars());
+
+        let cells: Vec<_> = grid.par_iter().collect();
+        assert_eq!(cells, grid.iter().collect::<Vec<_>>());
+
+        let mut positions: Vec<_> = grid.par_positions_of(|&c| c == '^').collect();
+        positions.sort_by_key(|p| (p.1, p.0));
+        assert_eq!(
+            positions,
+            grid.positions_of(|&c| c == '^').collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn grid_pack() {
+        let grid = Grid2D::from("abcd\nefgh\nijkl".chars());
+
+        assert_eq!(grid.pack(Vec2(0, 0)), 0);
+        assert_eq!(grid.pack(Vec2(2, 1)), 6);
+        for (p, _) in grid.iter() {
+            assert_eq!(grid.unpack(grid.pack(p)), p);
+        }
+    }
+
+    #[test]
+    fn position_set() {
+        let grid = Grid2D::new(70, 3, '.');
+        let mut set = grid.position_set();
+        assert!(set.is_empty());
+
+        assert!(set.insert(Vec2(69, 2)));
+        assert!(set.insert(Vec2(63, 0)));
+        assert!(set.insert(Vec2(0, 1)));
+        assert!(!set.insert(Vec2(63, 0)));
+
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(Vec2(0, 1)));
+        assert!(!set.contains(Vec2(1, 1)));
+        assert!(!set.contains(Vec2(70, 0)));
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [Vec2(63, 0), Vec2(0, 1), Vec2(69, 2)]
+        );
+
+        set.clear();
+        assert!(set.is_empty());
+        assert_eq!(set.iter().count(), 0);
+    }
+
+    #[test]
+    fn grid_iter() {
+        let grid = Grid2D::from("abcd\nefgh\nijkl".chars());
+
+        let mut iter = grid.iter();
+
+        assert_eq!(iter.next(), Some((Vec2(0, 0), &'a')));
+        assert_eq!(iter.next(), Some((Vec2(1, 0), &'b')));
+        assert_eq!(iter.next(), Some((Vec2(2, 0), &'c')));
+        assert_eq!(iter.next(), Some((Vec2(3, 0), &'d')));
+        assert_eq!(iter.next(), Some((Vec2(0, 1), &'e')));
+    }
+
+    #[test]
+    fn grid_set() {
+        let mut grid = Grid2D::from("abcd\nefgh\nijkl".chars());
+
+        assert_eq!(grid.set(Vec2(2, 1), '#'), Some('g'));
+        assert_eq!(grid.get(Vec2(2, 1)), Some(&'#'));
+        assert_eq!(grid.set(Vec2(4, 1), '#'), None);
+
+        *grid.get_mut(Vec2(0, 2)).unwrap() = '.';
+        assert_eq!(grid.get(Vec2(0, 2)), Some(&'.'));
+        assert_eq!(grid.get_mut(Vec2(0, 3)), None);
+    }
+
+    #[test]
+    fn grid_iter_mut() {
+        let mut grid = Grid2D::from("abcd\nefgh\nijkl".chars());
+
+        for (p, c) in grid.iter_mut() {
+            if p.0 == p.1 {
+                *c = c.to_ascii_uppercase();
+            }
+        }
+
+        let cells: String = grid.iter().map(|(_, c)| c).collect();
+        assert_eq!(cells, "AbcdeFghijKl");
+    }
+
+    #[test]
+    fn grid_from_chars_map() {
+        let grid = Grid2D::from_chars_map("012\n3.5\n".chars(), |c| c.to_digit(10))
+            .expect("rows to be the same width");
+
+        assert_eq!(grid.max(), Vec2(2, 1));
+        assert_eq!(grid.get(Vec2(1, 0)), Some(&Some(1)));
+        assert_eq!(grid.get(Vec2(1, 1)), Some(&None));
+        assert_eq!(grid.get(Vec2(2, 1)), Some(&Some(5)));
+
+        assert!(Grid2D::from_chars_map("8901\n781\n".chars(), |c| c).is_err());
+        assert!(Grid2D::from_chars_map("89\n78\n\n".chars(), |c| c).is_err());
+    }
+
+    #[test]
+    fn grid_display() {
+        let mut grid = Grid2D::from("abcd\nefgh\nijkl".chars());
+        grid.set(Vec2(1, 1), '#');
+
+        assert_eq!(grid.to_string(), "abcd\ne#gh\nijkl");
+
+        let grid = Grid2D::from_chars_map("12\n34".chars(), |c| c.to_digit(10).unwrap() * 2)
+            .expect("rows to be the same width");
+        assert_eq!(grid.to_string(), "24\n68");
+    }
+
+    #[test]
+    fn grid_find() {
+        let grid = Grid2D::from("ab^d\ne^gh\nijk^".chars());
+
+        assert_eq!(grid.find(|&c| c == '^'), Some(Vec2(2, 0)));
+        assert_eq!(grid.find(|&c| c == '#'), None);
+
+        let positions: Vec<_> = grid.positions_of(|&c| c == '^').collect();
+        assert_eq!(positions, [Vec2(2, 0), Vec2(1, 1), Vec2(3, 2)]);
+    }
+
+    #[test]
+    fn grid_bfs() {
+        let grid = Grid2D::from("..#.\n.##.\n....".chars());
+
+        let bfs = grid.bfs(Vec2(0, 0), |p, _| {
+            grid.neighbors4(p)
+                .filter(|(_, c)| **c == '.')
+                .map(|(p, _)| p)
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(bfs.order.len(), 9);
+        assert_eq!(bfs.order[0], Vec2(0, 0));
+        assert_eq!(bfs.distance(Vec2(1, 0)), Some(1));
+        assert_eq!(bfs.distance(Vec2(0, 2)), Some(2));
+        assert_eq!(bfs.distance(Vec2(3, 0)), Some(7));
+        assert_eq!(bfs.distance(Vec2(2, 0)), None);
+        assert_eq!(bfs.distance(Vec2(9, 9)), None);
+    }
+
+    #[test]
+    fn grid_flood_fill() {
+        let grid = Grid2D::from("AAAA\nBBCD\nBBCC\nEEEC".chars());
+
+        let region = grid.flood_fill(Vec2(2, 1));
+        assert_eq!(region.len(), 4);
+        assert!(region.contains(&Vec2(3, 3)));
+        assert!(!region.contains(&Vec2(3, 1)));
+        assert!(grid.flood_fill(Vec2(4, 0)).is_empty());
+    }
+
+    #[test]
+    fn grid_label_regions() {
+        let grid = Grid2D::from("AAAA\nBBCD\nBBCC\nEEEC".chars());
+
+        let RegionLabels { labels, regions } = grid.label_regions(|a, b| a == b);
+        assert_eq!(labels.to_string(), "0000\n1123\n1122\n4442");
+        assert_eq!(regions.len(), 5);
+        assert_eq!(
+            regions[2],
+            RegionSummary {
+                size: 4,
+                bounds: BoundingBox {
+                    min: Vec2(2, 1),
+                    max: Vec2(3, 3)
+                }
+            }
+        );
+        assert_eq!(regions[3].size, 1);
+    }
+
+    #[test]
+    fn region_metrics() {
+        let grid = Grid2D::from("AAAA\nBBCD\nBBCC\nEEEC".chars());
+
+        let metrics: Vec<_> = grid
+            .regions(|a, b| a == b)
+            .iter()
+            .map(|r| (r.area(), r.perimeter(), r.count_sides()))
+            .collect();
+        assert_eq!(
+            metrics,
+            [(4, 10, 4), (4, 8, 4), (4, 10, 8), (1, 4, 4), (3, 8, 4)]
+        );
+    }
+
+    #[test]
+    fn region_with_holes() {
+        let grid = Grid2D::from("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO".chars());
+        let region = grid.flood_fill(Vec2(0, 0));
+
+        assert_eq!(region.area(), 21);
+        assert_eq!(region.perimeter(), 36);
+        assert_eq!(region.count_sides(), 20);
+
+        let grid = Grid2D::from("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE".chars());
+        let region = grid.flood_fill(Vec2(0, 0));
+
+        assert_eq!(region.area(), 17);
+        assert_eq!(region.count_sides(), 12);
+    }
+
+    #[test]
+    fn grid_regions() {
+        let grid = Grid2D::from("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO".chars());
+
+        let regions = grid.regions(|a, b| a == b);
+        let sizes: Vec<_> = regions.iter().map(|r| r.len()).collect();
+        assert_eq!(sizes, [21, 1, 1, 1, 1]);
+
+        let regions = grid.regions(|_, _| true);
+        assert_eq!(regions.len(), 1);
+    }
+
+    #[test]
+    fn bounding_box() {
+        assert_eq!(BoundingBox::from_points([]), None);
+
+        let mut bounds = BoundingBox::from_points([Vec2(2, -1), Vec2(-1, 3), Vec2(0, 0)])
+            .expect("bounds around the points");
+        assert_eq!(bounds.min, Vec2(-1, -1));
+        assert_eq!(bounds.max, Vec2(2, 3));
+        assert_eq!(bounds.area(), 20);
+        assert!(bounds.contains(Vec2(2, 3)));
+        assert!(!bounds.contains(Vec2(3, 0)));
+
+        bounds.include(Vec2(3, 0));
+        assert!(bounds.contains(Vec2(3, 0)));
+        assert_eq!((bounds.width(), bounds.height()), (5, 5));
+
+        let bounds = bounds.expand(1);
+        assert_eq!(bounds.min, Vec2(-2, -2));
+        assert_eq!(bounds.area(), 49);
+    }
+
+    #[test]
+    fn sparse_grid() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        assert_eq!(grid.set(Vec2(-3, 2), 'a'), None);
+        assert_eq!(grid.set(Vec2(0, 0), 'b'), None);
+        assert_eq!(grid.set(Vec2(-2, 2), 'c'), None);
+        assert_eq!(grid.set(Vec2(0, 0), 'd'), Some('b'));
+        *grid.get_mut(Vec2(-2, 2)).unwrap() = 'e';
+
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.get(Vec2(-3, 2)), Some(&'a'));
+        assert_eq!(grid.get(Vec2(-2, 2)), Some(&'e'));
+        assert_eq!(grid.get(Vec2(1, 1)), None);
+        assert_eq!(
+            grid.bounds(),
+            BoundingBox::from_points([Vec2(-3, 0), Vec2(0, 2)])
+        );
+
+        let neighbors: Vec<_> = grid.neighbors4(Vec2(-2, 2)).collect();
+        assert_eq!(neighbors, [(Vec2(-3, 2), &'a')]);
+        assert_eq!(grid.neighbors8(Vec2(-1, 1)).count(), 2);
+
+        assert_eq!(grid.remove(Vec2(0, 0)), Some('d'));
+        assert_eq!(grid.bounds().map(|b| b.area()), Some(2));
+    }
+
+    #[test]
+    fn grid_diff() {
+        let before = Grid2D::from("..#\n.@.\n...".chars());
+        let mut after = Grid2D::from("..#\n.@.\n...".chars());
+        assert_eq!(before.diff(&after).count(), 0);
+
+        after.set(Vec2(1, 1), '.');
+        after.set(Vec2(1, 0), '@');
+        let changes: Vec<_> = before.diff(&after).collect();
+        assert_eq!(
+            changes,
+            [(Vec2(1, 0), &'.', &'@'), (Vec2(1, 1), &'@', &'.')]
+        );
+
+        let smaller = Grid2D::from("..\n..".chars());
+        let changes: Vec<_> = before.diff(&smaller).map(|(p, _, _)| p).collect();
+        assert_eq!(changes, [Vec2(1, 1)]);
+    }
+
+    #[test]
+    fn grid_windows() {
+        let grid = Grid2D::from("abcd\nefgh\nijkl".chars());
+
+        let windows: Vec<_> = grid.windows(Vec2(3, 2)).collect();
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows[3].origin(), Vec2(1, 1));
+        assert_eq!(windows[3][Vec2(0, 0)], 'f');
+        assert_eq!(windows[3][Vec2(2, 1)], 'l');
+        assert_eq!(windows[3].get(Vec2(3, 0)), None);
+
+        assert_eq!(grid.windows(Vec2(4, 3)).count(), 1);
+        assert_eq!(grid.windows(Vec2(5, 1)).count(), 0);
+    }
+
+    #[test]
+    fn grid_map() {
+        let grid = Grid2D::from("#.#\n..#".chars());
+
+        let walls = grid.map(|&c| c == '#');
+        assert_eq!(walls.max(), grid.max());
+        assert_eq!(walls.get(Vec2(2, 1)), Some(&true));
+        assert_eq!(walls.get(Vec2(1, 0)), Some(&false));
+        assert_eq!(walls.map(|&w| u8::from(w)).to_string(), "101\n001");
+    }
+
+    #[test]
+    fn grid_get_wrapped() {
+        let grid = Grid2D::from("abcd\nefgh\nijkl".chars());
+
+        assert_eq!(grid.get_wrapped(Vec2(1, 1)), &'f');
+        assert_eq!(grid.get_wrapped(Vec2(-1, 0)), &'d');
+        assert_eq!(grid.get_wrapped(Vec2(4, -1)), &'i');
+        assert_eq!(grid.get_wrapped(Vec2(-9, 7)), &'h');
+    }
+
+    #[test]
+    fn grid_in_bounds() {
+        let grid = Grid2D::from("abcd\nefgh\nijkl".chars());
+
+        assert!(grid.in_bounds(Vec2(0, 0)));
+        assert!(grid.in_bounds(Vec2(3, 2)));
+        assert!(!grid.in_bounds(Vec2(-1, 0)));
+        assert!(!grid.in_bounds(Vec2(4, 0)));
+        assert!(!grid.in_bounds(Vec2(0, 3)));
+    }
+
+    #[test]
+    fn grid_border() {
+        let grid = Grid2D::from("abcd\nefgh\nijkl\nmnop".chars());
+        let border: String = grid.border().map(|(_, c)| c).collect();
+        assert_eq!(border, "abcdehilmnop");
+
+        let grid = Grid2D::from("a\nb\nc".chars());
+        let border: String = grid.border().map(|(_, c)| c).collect();
+        assert_eq!(border, "abc");
+
+        let grid = Grid2D::from("abc".chars());
+        let border: Vec<_> = grid.border().map(|(p, _)| p).collect();
+        assert_eq!(border, [Vec2(0, 0), Vec2(1, 0), Vec2(2, 0)]);
+    }
+
+    #[test]
+    fn grid_render() {
+        let grid = Grid2D::from("..#\n.^.\n...".chars());
+
+        let walls = grid.map(|&c| c == '#');
+        assert_eq!(
+            walls.render_with(|_, &wall| if wall { '#' } else { ' ' }),
+            "  #\n   \n   "
+        );
+
+        let path = [Vec2(1, 0), Vec2(1, 1), Vec2(1, 2), Vec2(5, 5)];
+        assert_eq!(grid.render_highlight(path, 'X'), ".X#\n.X.\n.X.");
+    }
+
+    #[test]
+    fn grid_round_trip() {
+        for input in ["abcd\nefgh\nijkl", "#.\n.#\n", "x"] {
+            let grid = Grid2D::from(input.chars());
+            assert_eq!(grid.to_string(), input.trim_end());
+            assert_eq!(Grid2D::from(grid.to_string().chars()), grid);
+        }
+
+        let mut grid = Grid2D::from("....\n.@..\n....".chars());
+        grid.set(Vec2(1, 1), '.');
+        grid.set(Vec2(3, 2), '@');
+        assert_eq!(grid.to_string(), "....\n....\n...@");
+        assert_eq!(Grid2D::from(grid.to_string().chars()), grid);
+    }
+
+    #[test]
+    fn grid_traversal_orders() {
+        let grid = Grid2D::from("abc\ndef".chars());
+        let lines = |lines: Vec<String>| lines.join(" ");
+
+        let rows = grid.rows().map(|r| r.iter().collect()).collect();
+        assert_eq!(lines(rows), "abc def");
+
+        let columns: String = grid.iter_column_major().map(|(_, c)| c).collect();
+        assert_eq!(columns, "adbecf");
+
+        let diagonals = grid
+            .iter_diagonals()
+            .map(|d| d.map(|(_, c)| c).collect())
+            .collect();
+        assert_eq!(lines(diagonals), "d ae bf c");
+
+        let anti_diagonals = grid
+            .iter_anti_diagonals()
+            .map(|d| d.map(|(_, c)| c).collect())
+            .collect();
+        assert_eq!(lines(anti_diagonals), "a bd ce f");
+
+        let positions: Vec<_> = grid
+            .iter_diagonals()
+            .nth(1)
+            .unwrap()
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(positions, [Vec2(0, 0), Vec2(1, 1)]);
+    }
+
+    #[test]
+    fn grid_neighbors() {
+        let grid = Grid2D::from("abcd\nefgh\nijkl".chars());
+
+        let neighbors: String = grid.neighbors4(Vec2(0, 0)).map(|(_, c)| c).collect();
+        assert_eq!(neighbors, "eb");
+        let neighbors: String = grid.neighbors4(Vec2(1, 1)).map(|(_, c)| c).collect();
+        assert_eq!(neighbors, "jbge");
+        let neighbors: Vec<_> = grid.neighbors8(Vec2(3, 2)).collect();
+        assert_eq!(
+            neighbors,
+            [(Vec2(2, 2), &'k'), (Vec2(2, 1), &'g'), (Vec2(3, 1), &'h')]
+        );
+        assert_eq!(grid.neighbors8(Vec2(1, 1)).count(), 8);
     }
 }
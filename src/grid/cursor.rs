@@ -0,0 +1,110 @@
+use gxhash::{HashSet, HashSetExt};
+
+use super::{Direction, Grid2D, Vec2};
+
+/// Something walking around a [Grid2D], like a guard or a robot: a position and the direction it
+/// is facing. The grid is passed to each move rather than borrowed, so it can be changed between
+/// steps.
+pub struct GridCursor {
+    pub position: Vec2<usize>,
+    pub direction: Direction,
+    /// The position and direction after each turn so far, when detecting loops.
+    seen: Option<HashSet<(Vec2<usize>, Direction)>>,
+    looped: bool,
+}
+
+impl GridCursor {
+    pub fn new(position: Vec2<usize>, direction: Direction) -> Self {
+        Self {
+            position,
+            direction,
+            seen: None,
+            looped: false,
+        }
+    }
+
+    /// Remembers the position and direction after every turn from here on, so that
+    /// [GridCursor::looped] reports when the cursor makes a turn it has already made. Only moving
+    /// forward always ends at the edge of the grid, so any loop has to repeat a turn, and
+    /// checking only turns is much cheaper than checking every step.
+    pub fn detect_loops(mut self) -> Self {
+        let mut seen = HashSet::new();
+        seen.insert((self.position, self.direction));
+        self.seen = Some(seen);
+        self
+    }
+
+    /// Whether the cursor has made its latest turn before, when detecting loops. From then on it
+    /// would only go round the same path again.
+    pub fn looped(&self) -> bool {
+        self.looped
+    }
+
+    /// The position and cell straight ahead, or `None` if that is beyond the edge of the grid.
+    pub fn peek_ahead<'a, T>(&self, grid: &'a Grid2D<T>) -> Option<(Vec2<usize>, &'a T)> {
+        let p = self.position.try_add(self.direction.delta(), grid.max())?;
+        Some((p, grid.get(p)?))
+    }
+
+    /// Moves one cell forward, returning the new position, or `None` without moving if that would
+    /// go beyond the edge of the grid.
+    pub fn step<T>(&mut self, grid: &Grid2D<T>) -> Option<Vec2<usize>> {
+        let (p, _) = self.peek_ahead(grid)?;
+        self.position = p;
+        Some(p)
+    }
+
+    pub fn turn_right(&mut self) {
+        self.direction = self.direction.turn_right();
+        self.visit();
+    }
+
+    #[allow(unused)]
+    pub fn turn_left(&mut self) {
+        self.direction = self.direction.turn_left();
+        self.visit();
+    }
+
+    fn visit(&mut self) {
+        if let Some(seen) = &mut self.seen {
+            self.looped |= !seen.insert((self.position, self.direction));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cursor_walks() {
+        let grid = Grid2D::from("..#\n...\n...".chars());
+        let mut cursor = GridCursor::new(Vec2(1, 2), Direction::Up);
+
+        assert_eq!(cursor.peek_ahead(&grid), Some((Vec2(1, 1), &'.')));
+        assert_eq!(cursor.step(&grid), Some(Vec2(1, 1)));
+        cursor.turn_right();
+        assert_eq!(cursor.step(&grid), Some(Vec2(2, 1)));
+        assert_eq!(cursor.step(&grid), None);
+        assert_eq!(cursor.position, Vec2(2, 1));
+
+        cursor.turn_left();
+        assert_eq!(cursor.direction, Direction::Up);
+        assert_eq!(cursor.peek_ahead(&grid), Some((Vec2(2, 0), &'#')));
+        assert!(!cursor.looped());
+    }
+
+    #[test]
+    fn cursor_detects_loops() {
+        let grid = Grid2D::from("...\n...".chars());
+        let mut cursor = GridCursor::new(Vec2(0, 0), Direction::Right).detect_loops();
+
+        for _ in 0..4 {
+            assert!(!cursor.looped());
+            cursor.step(&grid);
+            cursor.turn_right();
+        }
+        assert!(cursor.looped());
+        assert_eq!(cursor.position, Vec2(0, 0));
+    }
+}
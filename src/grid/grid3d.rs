@@ -0,0 +1,244 @@
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    ops::{Add, Index, IndexMut, Mul, Neg, Sub},
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Vec3<I>(pub I, pub I, pub I);
+
+impl Vec3<usize> {
+    #[allow(unused)]
+    pub fn try_add(&self, d: Vec3<isize>, max: Vec3<usize>) -> Option<Vec3<usize>> {
+        let Vec3(x, y, z) = self.signed() + d;
+        let (x, y, z) = (
+            usize::try_from(x).ok()?,
+            usize::try_from(y).ok()?,
+            usize::try_from(z).ok()?,
+        );
+
+        if x > max.0 || y > max.1 || z > max.2 {
+            return None;
+        }
+
+        Some(Vec3(x, y, z))
+    }
+
+    #[allow(unused)]
+    pub fn try_subtract(&self, d: Vec3<isize>, max: Vec3<usize>) -> Option<Vec3<usize>> {
+        self.try_add(-d, max)
+    }
+
+    #[allow(unused)]
+    pub fn subtract(&self, d: Vec3<usize>) -> Vec3<isize> {
+        self.signed() - d.signed()
+    }
+
+    fn signed(&self) -> Vec3<isize> {
+        Vec3(self.0 as isize, self.1 as isize, self.2 as isize)
+    }
+}
+
+impl<I: Add<Output = I>> Add for Vec3<I> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Vec3(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+    }
+}
+
+impl<I: Sub<Output = I>> Sub for Vec3<I> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Vec3(self.0 - rhs.0, self.1 - rhs.1, self.2 - rhs.2)
+    }
+}
+
+impl<I: Neg<Output = I>> Neg for Vec3<I> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Vec3(-self.0, -self.1, -self.2)
+    }
+}
+
+impl<I: Mul<Output = I> + Copy> Mul<I> for Vec3<I> {
+    type Output = Self;
+
+    fn mul(self, rhs: I) -> Self {
+        Vec3(self.0 * rhs, self.1 * rhs, self.2 * rhs)
+    }
+}
+
+impl<I: Debug> Debug for Vec3<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({:?}, {:?}, {:?})", self.0, self.1, self.2)
+    }
+}
+
+/// Steps to the six cells sharing a face with a cell.
+pub const DIRECTIONS_6: [Vec3<isize>; 6] = [
+    Vec3(1, 0, 0),
+    Vec3(-1, 0, 0),
+    Vec3(0, 1, 0),
+    Vec3(0, -1, 0),
+    Vec3(0, 0, 1),
+    Vec3(0, 0, -1),
+];
+
+/// A three dimensional version of [Grid2D](super::Grid2D), stored layer by layer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid3D<T> {
+    width: usize,
+    height: usize,
+    depth: usize,
+    data: Vec<T>,
+}
+
+impl<T> Grid3D<T> {
+    #[allow(unused)]
+    /// A `width` by `height` by `depth` grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, depth: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            depth,
+            data: vec![fill; width * height * depth],
+        }
+    }
+
+    #[allow(unused)]
+    pub fn max(&self) -> Vec3<usize> {
+        Vec3(self.width - 1, self.height - 1, self.depth - 1)
+    }
+
+    #[allow(unused)]
+    pub fn get(&self, p: Vec3<usize>) -> Option<&T> {
+        if !self.contains(p) {
+            return None;
+        }
+
+        self.data.get(self.idx(p))
+    }
+
+    #[allow(unused)]
+    pub fn get_mut(&mut self, p: Vec3<usize>) -> Option<&mut T> {
+        if !self.contains(p) {
+            return None;
+        }
+
+        let idx = self.idx(p);
+        self.data.get_mut(idx)
+    }
+
+    #[allow(unused)]
+    /// Replaces the cell at `p` with `value`, returning the old value, or `None` if `p` is beyond
+    /// the edge of the grid.
+    pub fn set(&mut self, p: Vec3<usize>, value: T) -> Option<T> {
+        self.get_mut(p).map(|cell| std::mem::replace(cell, value))
+    }
+
+    #[allow(unused)]
+    /// The cells sharing a face with `p`, leaving out any beyond the edge of the grid.
+    pub fn neighbors6(&self, p: Vec3<usize>) -> impl Iterator<Item = (Vec3<usize>, &T)> {
+        DIRECTIONS_6.iter().filter_map(move |d| {
+            let p = p.try_add(*d, self.max())?;
+            Some((p, self.get(p)?))
+        })
+    }
+
+    #[allow(unused)]
+    /// Every cell, row by row within each layer.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec3<usize>, &T)> {
+        let (width, height) = (self.width, self.height);
+        self.data.iter().enumerate().map(move |(i, cell)| {
+            let p = Vec3(i % width, i / width % height, i / (width * height));
+            (p, cell)
+        })
+    }
+
+    fn contains(&self, p: Vec3<usize>) -> bool {
+        p.0 < self.width && p.1 < self.height && p.2 < self.depth
+    }
+
+    fn idx(&self, Vec3(x, y, z): Vec3<usize>) -> usize {
+        (z * self.height + y) * self.width + x
+    }
+}
+
+/// Like [Grid3D::get], but panics if `p` is beyond the edge of the grid.
+impl<T> Index<Vec3<usize>> for Grid3D<T> {
+    type Output = T;
+
+    fn index(&self, p: Vec3<usize>) -> &T {
+        self.get(p)
+            .unwrap_or_else(|| panic!("{p:?} is beyond the edge of the grid"))
+    }
+}
+
+/// Like [Grid3D::get_mut], but panics if `p` is beyond the edge of the grid.
+impl<T> IndexMut<Vec3<usize>> for Grid3D<T> {
+    fn index_mut(&mut self, p: Vec3<usize>) -> &mut T {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{p:?} is beyond the edge of the grid"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn vec3_checked() {
+        let max = Vec3(3, 3, 3);
+
+        assert_eq!(
+            Vec3(1, 1, 1).try_add(Vec3(2, -1, 0), max),
+            Some(Vec3(3, 0, 1))
+        );
+        assert_eq!(Vec3(1, 1, 1).try_add(Vec3(0, 0, -2), max), None);
+        assert_eq!(Vec3(1, 1, 1).try_add(Vec3(0, 3, 0), max), None);
+        assert_eq!(
+            Vec3(1, 1, 1).try_subtract(Vec3(-1, 1, 1), max),
+            Some(Vec3(2, 0, 0))
+        );
+        assert_eq!(Vec3(1, 3, 0).subtract(Vec3(2, 1, 4)), Vec3(-1, 2, -4));
+    }
+
+    #[test]
+    fn vec3_ops() {
+        let p = Vec3(1_i64, -2, 3);
+
+        assert_eq!(p + Vec3(1, 1, 1) * 2, Vec3(3, 0, 5));
+        assert_eq!(p - Vec3(1, 1, 1), Vec3(0, -3, 2));
+        assert_eq!(-p, Vec3(-1, 2, -3));
+    }
+
+    #[test]
+    fn grid3d() {
+        let mut grid = Grid3D::new(3, 2, 4, '.');
+        assert_eq!(grid.max(), Vec3(2, 1, 3));
+
+        assert_eq!(grid.set(Vec3(2, 1, 3), '#'), Some('.'));
+        grid[Vec3(0, 1, 2)] = '@';
+        assert_eq!(grid.get(Vec3(2, 1, 3)), Some(&'#'));
+        assert_eq!(grid[Vec3(0, 1, 2)], '@');
+        assert_eq!(grid.get(Vec3(3, 0, 0)), None);
+        assert_eq!(grid.get(Vec3(0, 0, 4)), None);
+
+        let cells: Vec<_> = grid.iter().filter(|(_, c)| **c != '.').collect();
+        assert_eq!(cells, [(Vec3(0, 1, 2), &'@'), (Vec3(2, 1, 3), &'#')]);
+
+        assert_eq!(grid.neighbors6(Vec3(0, 0, 0)).count(), 3);
+        assert_eq!(grid.neighbors6(Vec3(1, 1, 1)).count(), 5);
+        let neighbors: Vec<_> = grid
+            .neighbors6(Vec3(0, 1, 3))
+            .filter(|(_, c)| **c != '.')
+            .collect();
+        assert_eq!(neighbors, [(Vec3(0, 1, 2), &'@')]);
+    }
+}
@@ -0,0 +1,187 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A position on a hexagonal grid in axial coordinates, where `q` runs along one axis of the hex
+/// grid and `r` along another. The third, implied, coordinate is `-q - r`; see [Cube].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Hex {
+    pub q: i64,
+    pub r: i64,
+}
+
+/// Steps to the six hexes sharing an edge with a hex, going round anticlockwise from `+q`.
+pub const HEX_DIRECTIONS: [Hex; 6] = [
+    Hex::new(1, 0),
+    Hex::new(1, -1),
+    Hex::new(0, -1),
+    Hex::new(-1, 0),
+    Hex::new(-1, 1),
+    Hex::new(0, 1),
+];
+
+impl Hex {
+    pub const fn new(q: i64, r: i64) -> Self {
+        Self { q, r }
+    }
+
+    #[allow(unused)]
+    pub fn s(&self) -> i64 {
+        -self.q - self.r
+    }
+
+    #[allow(unused)]
+    pub fn neighbors(&self) -> impl Iterator<Item = Hex> + '_ {
+        HEX_DIRECTIONS.iter().map(|d| *self + *d)
+    }
+
+    #[allow(unused)]
+    /// The number of steps between the two hexes.
+    pub fn distance(&self, other: &Self) -> u64 {
+        Cube::from(*self).distance(&Cube::from(*other))
+    }
+
+    #[allow(unused)]
+    /// Rotates a sixth of the way clockwise around the origin.
+    pub fn rotate_clockwise(&self) -> Self {
+        Cube::from(*self).rotate_clockwise().into()
+    }
+
+    #[allow(unused)]
+    /// Rotates a sixth of the way anticlockwise around the origin.
+    pub fn rotate_anticlockwise(&self) -> Self {
+        Cube::from(*self).rotate_anticlockwise().into()
+    }
+}
+
+impl Add for Hex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+impl Mul<i64> for Hex {
+    type Output = Self;
+
+    fn mul(self, rhs: i64) -> Self {
+        Hex::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+/// A position on a hexagonal grid in cube coordinates, which always sum to zero. The same
+/// position as a [Hex], with the implied coordinate spelled out, which makes distances and
+/// rotations symmetric.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Cube {
+    pub q: i64,
+    pub r: i64,
+    pub s: i64,
+}
+
+impl Cube {
+    #[allow(unused)]
+    /// The number of steps between the two positions.
+    pub fn distance(&self, other: &Self) -> u64 {
+        self.q
+            .abs_diff(other.q)
+            .max(self.r.abs_diff(other.r))
+            .max(self.s.abs_diff(other.s))
+    }
+
+    #[allow(unused)]
+    /// Rotates a sixth of the way clockwise around the origin.
+    pub fn rotate_clockwise(&self) -> Self {
+        Cube {
+            q: -self.r,
+            r: -self.s,
+            s: -self.q,
+        }
+    }
+
+    #[allow(unused)]
+    /// Rotates a sixth of the way anticlockwise around the origin.
+    pub fn rotate_anticlockwise(&self) -> Self {
+        Cube {
+            q: -self.s,
+            r: -self.q,
+            s: -self.r,
+        }
+    }
+}
+
+impl From<Hex> for Cube {
+    fn from(hex: Hex) -> Self {
+        Cube {
+            q: hex.q,
+            r: hex.r,
+            s: hex.s(),
+        }
+    }
+}
+
+impl From<Cube> for Hex {
+    fn from(cube: Cube) -> Self {
+        Hex::new(cube.q, cube.r)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hex_neighbors() {
+        let origin = Hex::default();
+
+        for (n, d) in origin.neighbors().zip(HEX_DIRECTIONS) {
+            assert_eq!(n, d);
+            assert_eq!(origin.distance(&n), 1);
+        }
+        assert_eq!(Hex::new(2, -1).neighbors().nth(3), Some(Hex::new(1, -1)));
+    }
+
+    #[test]
+    fn hex_distance() {
+        assert_eq!(Hex::new(0, 0).distance(&Hex::new(3, -3)), 3);
+        assert_eq!(Hex::new(-1, 2).distance(&Hex::new(2, 0)), 3);
+        assert_eq!(Hex::new(1, 1).distance(&Hex::new(-2, -2)), 6);
+
+        let walk = HEX_DIRECTIONS[0] * 3 + HEX_DIRECTIONS[2] * 2;
+        assert_eq!(walk, Hex::new(3, -2));
+        assert_eq!(Hex::default().distance(&walk), 3);
+    }
+
+    #[test]
+    fn hex_rotation() {
+        for (i, d) in HEX_DIRECTIONS.iter().enumerate() {
+            assert_eq!(d.rotate_anticlockwise(), HEX_DIRECTIONS[(i + 1) % 6]);
+            assert_eq!(d.rotate_clockwise(), HEX_DIRECTIONS[(i + 5) % 6]);
+        }
+
+        let p = Hex::new(2, -3);
+        let mut q = p;
+        for _ in 0..6 {
+            q = q.rotate_clockwise();
+            assert_eq!(q.distance(&Hex::default()), 3);
+        }
+        assert_eq!(q, p);
+        assert_eq!(Hex::from(Cube::from(p)), p);
+        assert_eq!(Cube::from(p).q + Cube::from(p).r + Cube::from(p).s, 0);
+    }
+}
@@ -1,3 +1,7 @@
+pub mod answers;
+pub mod aoc;
+pub mod clipboard;
+pub mod config;
 pub mod day;
 pub mod day_01;
 pub mod day_02;
@@ -10,6 +14,15 @@ pub mod day_08;
 pub mod day_09;
 pub mod day_10;
 pub mod day_11;
+pub mod day_25;
+pub mod error;
 mod grid;
-mod parser;
+pub mod log;
+pub mod memory;
+pub mod output;
+pub mod parser;
+pub mod runner;
+pub mod scaffold;
+mod search;
 pub mod test_util;
+mod toml;
@@ -0,0 +1,53 @@
+use std::{
+    fmt::Display,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+    time::Instant,
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// Turn on debug logging for the rest of the process, e.g. from `--debug`. Log lines are written
+/// to stderr, timed from when logging was enabled.
+pub fn enable() {
+    START.get_or_init(Instant::now);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Writes a log line. Use the [debug](crate::debug) macro rather than calling this directly, so
+/// that the fields are only formatted when logging is enabled.
+pub fn write(target: &str, message: &str, fields: &[(&str, &dyn Display)]) {
+    let elapsed = START.get().map(Instant::elapsed).unwrap_or_default();
+
+    let mut line = format!("[{elapsed:>10.3?}] DEBUG {target}: {message}");
+    for (key, value) in fields {
+        line.push_str(&format!(" {key}={value}"));
+    }
+
+    eprintln!("{line}");
+}
+
+/// Logs a debug event with optional `key = value` fields, when debug logging is enabled.
+///
+/// ```text
+/// debug!("blink", step = i, stones = cache.len());
+/// ```
+#[macro_export]
+macro_rules! debug {
+    ($message:literal $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::log::enabled() {
+            $crate::log::write(
+                module_path!(),
+                $message,
+                &[$((stringify!($key), &$value as &dyn std::fmt::Display)),*],
+            );
+        }
+    };
+}
@@ -1,73 +1,1458 @@
+use std::{
+    fs::File,
+    io::{stdin, BufReader, Cursor, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
 use adventofcode_2024::*;
+use anyhow::anyhow;
 use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(
     version,
     about,
-    long_about = "CLI for solving the Advent of Code 2024 puzzles."
+    long_about = "CLI for solving the Advent of Code 2024 puzzles.",
+    args_conflicts_with_subcommands = true
 )]
 struct Cli {
-    #[arg(short, long)]
+    /// Log parse progress and solver internals to stderr.
+    #[arg(short, long, global = true)]
     debug: bool,
 
+    /// Narrate how the answer is reached to stderr, for days that support it (such as day 5).
+    #[arg(long, global = true)]
+    explain: bool,
+
+    /// The day to solve, e.g. `adventofcode-2024 7`.
+    #[arg(value_name = "DAY")]
+    day: Option<u8>,
+
+    /// Which part of the puzzle to solve. Solves both parts when omitted.
+    #[arg(value_name = "PART")]
+    part: Option<Part>,
+
+    /// The day to solve, as an alternative to the DAY argument.
+    #[arg(long = "day", value_name = "DAY", conflicts_with = "day")]
+    day_flag: Option<u8>,
+
+    /// Which part to solve, as an alternative to the PART argument.
+    #[arg(long = "part", value_name = "PART", conflicts_with = "part")]
+    part_flag: Option<Part>,
+
+    /// Solve each part N times against a buffered copy of the input, reporting the best and mean
+    /// times.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    repeat: Option<u32>,
+
+    /// Report the peak heap usage and number of allocations of each part. Needs the CLI to be
+    /// built with `--features memory`.
+    #[arg(long, conflicts_with = "repeat")]
+    memory: bool,
+
+    /// Print nothing but the answer, without labels, timings or colour, e.g. for piping into
+    /// another command. When solving both parts, each answer is on its own line.
+    #[arg(short, long, conflicts_with = "output")]
+    quiet: bool,
+
+    /// Give up on a part that is still running after SECS seconds, e.g. because it is stuck in
+    /// an infinite loop.
+    #[arg(
+        long,
+        value_name = "SECS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with = "repeat"
+    )]
+    timeout: Option<u64>,
+
+    /// Copy the answer to the clipboard. When solving both parts, part two's answer is copied.
+    #[arg(long)]
+    copy: bool,
+
+    /// Append a `day,part,answer` line to FILE for each solved part.
+    #[arg(long, value_name = "FILE")]
+    out: Option<PathBuf>,
+
+    /// Read the puzzle input from a file instead of stdin, or from the system clipboard with
+    /// `--input clipboard`.
+    ///
+    /// When omitted and nothing is piped to stdin, the day's `day{N}.txt` is read from the input
+    /// directory (AOC_INPUT_DIR, `input_dir` from the config file, or `.input`) if it exists.
+    #[arg(short, long, global = true, value_name = "FILE")]
+    input: Option<PathBuf>,
+
+    /// Solve the day's example from the puzzle text instead of the puzzle input, checking the
+    /// answer against the expected one.
+    #[arg(short, long, global = true, conflicts_with = "input")]
+    example: bool,
+
+    /// Report how long each part took to solve, excluding reading the input.
+    #[arg(short, long, global = true)]
+    time: bool,
+
+    /// How answers (and errors) are written to stdout. Defaults to `output` from the config
+    /// file, or text.
+    #[arg(short, long = "output", id = "output", global = true, value_enum)]
+    output_flag: Option<Output>,
+
+    /// Never colour text output. Setting the NO_COLOR environment variable does the same.
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// The output format, resolved from --output and the config file by [Cli::configure].
+    #[arg(skip)]
+    output: Output,
+
+    /// Whether to colour text output, resolved from --no-color and the config file.
+    #[arg(skip)]
+    color: output::ColorChoice,
+
+    /// The default input directory, resolved from AOC_INPUT_DIR and the config file.
+    #[arg(skip)]
+    input_dir: PathBuf,
+
     #[command(subcommand)]
-    day: Day,
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve both parts of every day and print a summary table.
+    All {
+        /// Directory containing puzzle inputs named `day{N}.txt`. Defaults to AOC_INPUT_DIR,
+        /// `input_dir` from the config file, or `.input`.
+        #[arg(long, value_name = "DIR")]
+        input_dir: Option<PathBuf>,
+        /// Solve every day and part on its own thread.
+        #[arg(long)]
+        parallel: bool,
+    },
+    /// Solve every day against its input and print a results table, e.g. for the README.
+    Report {
+        /// The format of the table.
+        #[arg(long, value_enum, default_value_t = ReportFormat::Markdown)]
+        format: ReportFormat,
+        /// Leave out the answers, only reporting timings.
+        #[arg(long)]
+        redact: bool,
+        /// Directory containing puzzle inputs named `day{N}.txt`. Defaults to AOC_INPUT_DIR,
+        /// `input_dir` from the config file, or `.input`.
+        #[arg(long, value_name = "DIR")]
+        input_dir: Option<PathBuf>,
+    },
+    /// Print how many days and parts are implemented, how long they take to solve every input,
+    /// and how many stars have been collected when a session is configured.
+    Stats {
+        /// Directory containing puzzle inputs named `day{N}.txt`. Defaults to AOC_INPUT_DIR,
+        /// `input_dir` from the config file, or `.input`.
+        #[arg(long, value_name = "DIR")]
+        input_dir: Option<PathBuf>,
+        /// Manifest of known-good answers to count matches against, when it exists.
+        #[arg(long, value_name = "FILE", default_value = "answers.toml")]
+        answers: PathBuf,
+    },
+    /// Solve a part against its cached input and submit the answer to adventofcode.com.
+    ///
+    /// Uses the session cookie stored by `login`, or AOC_SESSION if set.
+    Submit {
+        /// The day to submit an answer for.
+        day: u8,
+        /// Which part of the puzzle to submit.
+        part: Part,
+        /// Directory containing puzzle inputs named `day{N}.txt`. Defaults to AOC_INPUT_DIR,
+        /// `input_dir` from the config file, or `.input`.
+        #[arg(long, value_name = "DIR")]
+        input_dir: Option<PathBuf>,
+    },
+    /// Time repeated runs of a day against a buffered copy of its input.
+    Bench {
+        /// The day to benchmark.
+        day: u8,
+        /// Which part to benchmark. Benchmarks both parts when omitted.
+        part: Option<Part>,
+        /// How many times to run each part.
+        #[arg(short = 'n', long, default_value_t = 10)]
+        iterations: usize,
+    },
+    /// Run a part and every alternative implementation registered for it against the same
+    /// input, checking that their answers agree and comparing their timings.
+    Compare {
+        /// The day to compare implementations of.
+        day: u8,
+        /// Which part to compare.
+        part: Part,
+        /// How many times to run each implementation.
+        #[arg(short = 'n', long, default_value_t = 10)]
+        iterations: usize,
+    },
+    /// Re-solve a day whenever its --input file changes, or the CLI is rebuilt.
+    Watch {
+        /// The day to solve.
+        day: u8,
+        /// Which part to solve. Solves both parts when omitted.
+        part: Option<Part>,
+    },
+    /// Check answers against a manifest of known-good answers, e.g. after a refactor.
+    Verify {
+        /// Only verify this day. Verifies every day when omitted.
+        day: Option<u8>,
+        /// Directory containing puzzle inputs named `day{N}.txt`. Defaults to AOC_INPUT_DIR,
+        /// `input_dir` from the config file, or `.input`.
+        #[arg(long, value_name = "DIR")]
+        input_dir: Option<PathBuf>,
+        /// Manifest of answers, with a `[dayN]` section holding `part1`/`part2` keys.
+        #[arg(long, value_name = "FILE", default_value = "answers.toml")]
+        answers: PathBuf,
+    },
+    /// Generate a new day module and register it in lib.rs and main.rs. Run this from the
+    /// repository root.
+    Scaffold {
+        /// The day to generate a module for.
+        day: u8,
+    },
+    /// Store an adventofcode.com session cookie for commands that use the website.
+    Login {
+        /// The session cookie. Read from stdin when omitted, to keep it out of shell
+        /// history.
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Remove the stored session cookie.
+    Logout,
+    /// Check the configured session cookie and print who it belongs to.
+    Whoami,
+}
+
+impl Command {
+    fn run(&self, cli: &Cli) -> anyhow::Result<Outcome> {
+        match self {
+            Self::All {
+                input_dir,
+                parallel,
+            } => solve_all(&registry(), cli.input_dir(input_dir), *parallel, cli).map(Outcome::All),
+            Self::Report {
+                format,
+                redact,
+                input_dir,
+            } => Ok(Outcome::Report {
+                format: *format,
+                redact: *redact,
+                runs: solve_all(&registry(), cli.input_dir(input_dir), false, cli)?,
+            }),
+            Self::Stats { input_dir, answers } => {
+                stats(registry(), cli.input_dir(input_dir), answers, cli)
+            }
+            Self::Submit {
+                day,
+                part,
+                input_dir,
+            } => submit(&find(*day)?, *part, cli.input_dir(input_dir), cli),
+            Self::Bench {
+                day,
+                part,
+                iterations,
+            } => bench(&find(*day)?, *part, *iterations, cli),
+            Self::Compare {
+                day,
+                part,
+                iterations,
+            } => compare(&find(*day)?, *part, *iterations, cli),
+            Self::Watch { day, part } => watch(&find(*day)?, *part, cli),
+            Self::Verify {
+                day,
+                input_dir,
+                answers,
+            } => verify(registry(), *day, cli.input_dir(input_dir), answers),
+            Self::Scaffold { day } => scaffold::scaffold(Path::new("."), *day)
+                .map(|module| Outcome::Message(format!("created {module}"))),
+            Self::Login { session } => login(session.as_deref()),
+            Self::Logout => logout(),
+            Self::Whoami => whoami(),
+        }
+    }
 }
 
+#[cfg(feature = "memory")]
+#[global_allocator]
+static ALLOCATOR: memory::CountingAllocator = memory::CountingAllocator;
+
 gen::days! {
-    Day01: day_01::solution(),
-    Day02: day_02::solution(),
-    Day03: day_03::solution(),
-    Day04: day_04::solution(),
-    Day05: day_05::solution(),
-    Day06: day_06::solution(),
-    Day07: day_07::solution(),
-    Day08: day_08::solution(),
-    Day09: day_09::solution(),
-    Day10: day_10::solution(),
-    Day11: day_11::solution()
+    1 => day_01::solution(),
+    2 => day_02::solution(),
+    3 => day_03::solution(),
+    4 => day_04::solution(),
+    5 => day_05::solution(),
+    6 => day_06::solution(),
+    7 => day_07::solution(),
+    8 => day_08::solution(),
+    9 => day_09::solution(),
+    10 => day_10::solution(),
+    11 => day_11::solution(),
+    25 => day_25::solution()
 }
 
 fn main() {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    if cli.debug {
+        log::enable();
+    }
+    if cli.explain {
+        day::enable_explanations();
+    }
+
+    let result = config::Config::load().and_then(|config| cli.configure(config));
+    if let Err(err) = result
+        .and_then(|_| cli.run())
+        .and_then(|outcome| deliver(&outcome, &cli).map(|_| outcome))
+        .and_then(|outcome| report(outcome, &cli))
+    {
+        exit_with(&err, cli.output, &cli.stderr_style());
+    }
+}
+
+/// Report `err` to the user in the requested output format, then exit with a status saying what
+/// kind of error it was.
+fn exit_with(err: &anyhow::Error, output: Output, style: &output::Style) -> ! {
+    match output {
+        Output::Text => eprintln!("{}", output::error_line(style, err)),
+        Output::Json => {
+            let mut json = match err.downcast_ref::<PartError>() {
+                Some(PartError { day, part }) => serde_json::json!({
+                    "day": day,
+                    "part": part,
+                    "error": format!("{:#}", err.root_cause()),
+                }),
+                None => serde_json::json!({ "error": format!("{err:#}") }),
+            };
+            if let Some(kind) = error::kind(err) {
+                json["kind"] = kind.name().into();
+            }
+            println!("{json}");
+        }
+    }
+    std::process::exit(error::kind(err).map_or(1, error::Kind::exit_code));
+}
+
+impl Cli {
+    /// Resolve settings that can come from the config file, environment or flags, in increasing
+    /// order of precedence.
+    fn configure(&mut self, config: config::Config) -> anyhow::Result<()> {
+        self.output = match (self.output_flag, &config.output) {
+            (Some(output), _) => output,
+            (None, _) if self.quiet => Output::Text,
+            (None, Some(output)) => Output::from_str(output, true)
+                .map_err(|_| anyhow!("config: unknown output format {output:?}"))?,
+            (None, None) => Output::default(),
+        };
+
+        self.color = if self.no_color {
+            output::ColorChoice::Never
+        } else {
+            config.color.unwrap_or_default()
+        };
+
+        self.input_dir = std::env::var_os("AOC_INPUT_DIR")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or(config.input_dir)
+            .unwrap_or_else(|| PathBuf::from(".input"));
+
+        Ok(())
+    }
+
+    /// The input directory given to a subcommand, or the configured default.
+    fn input_dir<'a>(&'a self, flag: &'a Option<PathBuf>) -> &'a Path {
+        flag.as_deref().unwrap_or(&self.input_dir)
+    }
+
+    /// The input to solve `day` against: the `--input` flag, or when stdin is a terminal (so
+    /// nothing is being piped in), the day's file in the input directory if there is one. `None`
+    /// means stdin.
+    fn input(&self, day: u8) -> Option<PathBuf> {
+        if self.input.is_some() || !stdin().is_terminal() {
+            return self.input.clone();
+        }
+
+        let path = runner::input_path(&self.input_dir, day);
+        if !path.is_file() {
+            return None;
+        }
+
+        eprintln!("-- reading input from {}", path.display());
+        Some(path)
+    }
+
+    fn run(&self) -> anyhow::Result<Outcome> {
+        if self.memory && !cfg!(feature = "memory") {
+            return Err(anyhow!(
+                "--memory needs the CLI to be built with `--features memory`"
+            ));
+        }
+
+        match (&self.command, self.day.or(self.day_flag)) {
+            (Some(command), _) => command.run(self),
+            (None, Some(day)) => {
+                let (n, day) = find(day)?;
+                let part = self.part.or(self.part_flag);
+                match self.repeat {
+                    Some(iterations) => repeat(n, &day, part, iterations as usize, self),
+                    None => solve(n, &day, part, self),
+                }
+            }
+            (None, None) => Err(anyhow!(
+                "pick a day to solve, e.g. `adventofcode-2024 7 2`, or a subcommand (see --help)"
+            )),
+        }
+    }
+
+    fn stdout_style(&self) -> output::Style {
+        output::Style::detect(self.color, std::io::stdout().is_terminal())
+    }
+
+    fn stderr_style(&self) -> output::Style {
+        output::Style::detect(self.color, std::io::stderr().is_terminal())
+    }
+}
+
+/// Context attached to the error of a part that failed to solve, so that the error can be
+/// reported against that day and part.
+#[derive(Debug)]
+struct PartError {
+    day: u8,
+    part: u8,
+}
 
-    if let Err(err) = cli.day.solve() {
-        eprintln!("Error: {err}");
-        std::process::exit(1);
+impl std::fmt::Display for PartError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "day {} part {} failed", self.day, self.part)
     }
 }
 
+/// What a subcommand produced. Subcommands return their results rather than printing them, and
+/// [report] prints them according to the output flags.
+enum Outcome {
+    /// One or more parts of a single day. Parts are labelled when both were requested.
+    Day {
+        day: u8,
+        labelled: bool,
+        runs: Vec<Solved>,
+    },
+    /// Every registered day, from the `all` subcommand.
+    All(Vec<runner::DayRun>),
+    /// Every registered day, rendered as a table by the `report` subcommand.
+    Report {
+        format: ReportFormat,
+        redact: bool,
+        runs: Vec<runner::DayRun>,
+    },
+    /// Aggregate figures over every registered day, from the `stats` subcommand.
+    Stats {
+        days: usize,
+        parts: usize,
+        totals: runner::Totals,
+        /// How many parts matched the answers manifest, and how many it records, if it exists.
+        answers: Option<(usize, usize)>,
+        stars: Option<u32>,
+    },
+    /// Timings from repeatedly running one or both parts of a day.
+    Bench {
+        day: u8,
+        timings: Vec<(Part, runner::Timings)>,
+    },
+    /// A part's implementations run against the same input, the registered one first.
+    Compare {
+        day: u8,
+        part: Part,
+        runs: Vec<(&'static str, runner::PartRun, Option<runner::Timings>)>,
+    },
+    /// Every requested day checked against a manifest of known-good answers.
+    Verify {
+        answers: answers::Answers,
+        runs: Vec<runner::DayRun>,
+    },
+    /// A one-line status message, e.g. from `login`.
+    Message(String),
+    /// An answer submitted to the AoC website, along with its verdict.
+    Submit {
+        day: u8,
+        part: u8,
+        answer: String,
+        verdict: aoc::Verdict,
+    },
+}
+
+/// A part solved by the normal solve flow, along with any measurements requested by flags.
+struct Solved {
+    part: Part,
+    run: runner::PartRun,
+    /// Timings over repeated runs, from `--repeat`.
+    timings: Option<runner::Timings>,
+    /// Heap usage, from `--memory`.
+    memory: Option<memory::Stats>,
+}
+
+impl Solved {
+    fn new(part: Part, run: runner::PartRun) -> Self {
+        Self {
+            part,
+            run,
+            timings: None,
+            memory: None,
+        }
+    }
+}
+
+/// Send the answers of a solved day to the destinations picked by `--copy` and `--out`, in
+/// addition to stdout. Parts that failed are skipped, leaving [report] to surface their errors.
+fn deliver(outcome: &Outcome, cli: &Cli) -> anyhow::Result<()> {
+    let Outcome::Day { day, runs, .. } = outcome else {
+        return Ok(());
+    };
+
+    let answers: Vec<_> = runs
+        .iter()
+        .filter_map(|solved| Some((solved.part.number(), solved.run.answer.as_ref().ok()?)))
+        .collect();
+
+    if let Some(path) = &cli.out {
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)
+            .map_err(|err| anyhow!("could not open {}: {err}", path.display()))?;
+        for (part, answer) in &answers {
+            writeln!(file, "{day},{part},{answer}")
+                .map_err(|err| anyhow!("could not write {}: {err}", path.display()))?;
+        }
+    }
+
+    if cli.copy {
+        if let Some((_, answer)) = answers.last() {
+            clipboard::copy(answer)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn report(outcome: Outcome, cli: &Cli) -> anyhow::Result<()> {
+    let style = cli.stdout_style();
+
+    match outcome {
+        Outcome::Day {
+            day,
+            labelled,
+            runs,
+        } => {
+            for Solved {
+                part,
+                run,
+                timings,
+                memory,
+            } in runs
+            {
+                let part = part.number();
+                let duration = run.duration;
+                let answer = run
+                    .answer
+                    .map_err(|err| err.context(PartError { day, part }))?;
+
+                match cli.output {
+                    Output::Json => {
+                        let mut json = part_json(
+                            day,
+                            part,
+                            &runner::PartRun {
+                                answer: Ok(answer),
+                                duration,
+                            },
+                        );
+                        if let Some(t) = timings {
+                            json["repeat"] = t.iterations.into();
+                            json["best_ms"] = (t.min.as_secs_f64() * 1000.0).into();
+                            json["mean_ms"] = (t.mean.as_secs_f64() * 1000.0).into();
+                        }
+                        if let Some(m) = memory {
+                            json["peak_bytes"] = m.peak_bytes.into();
+                            json["allocations"] = m.allocations.into();
+                        }
+                        println!("{json}");
+                    }
+                    Output::Text if cli.quiet => println!("{answer}"),
+                    Output::Text => {
+                        let timing = match timings {
+                            Some(t) => Some(output::repeat_timing(&t)),
+                            None => cli.time.then(|| output::duration(duration)),
+                        };
+                        let timing = match (timing, memory) {
+                            (Some(timing), Some(m)) => {
+                                Some(format!("{timing}, {}", output::memory(&m)))
+                            }
+                            (None, Some(m)) => Some(output::memory(&m)),
+                            (timing, None) => timing,
+                        };
+                        let label = if labelled {
+                            Some(format!("part {part}"))
+                        } else {
+                            timing.is_some().then(|| "answer".into())
+                        };
+                        println!(
+                            "{}",
+                            output::answer_line(
+                                &style,
+                                label.as_deref(),
+                                &answer,
+                                timing.as_deref()
+                            )
+                        );
+                    }
+                }
+            }
+
+            Ok(())
+        }
+        Outcome::All(runs) => {
+            match cli.output {
+                Output::Text => print!("{}", output::summary_table(&style, &runs)),
+                Output::Json => {
+                    for run in &runs {
+                        for (part, part_run) in run.parts() {
+                            println!("{}", part_json(run.day, part, part_run));
+                        }
+                    }
+                }
+            }
+
+            check_runs(&runs)
+        }
+        Outcome::Report {
+            format,
+            redact,
+            runs,
+        } => {
+            match format {
+                ReportFormat::Markdown => print!("{}", output::markdown_table(&runs, redact)),
+                ReportFormat::Csv => print!("{}", output::delimited_table(&runs, ',', redact)),
+                ReportFormat::Tsv => print!("{}", output::delimited_table(&runs, '\t', redact)),
+            }
+
+            check_runs(&runs)
+        }
+        Outcome::Stats {
+            days,
+            parts,
+            totals,
+            answers,
+            stars,
+        } => {
+            match cli.output {
+                Output::Text => {
+                    let mut lines = vec![
+                        ("days implemented", days.to_string()),
+                        ("parts implemented", parts.to_string()),
+                        ("parts solved", totals.solved.to_string()),
+                        ("total runtime", output::duration(totals.duration)),
+                    ];
+                    if let Some((day, part, duration)) = totals.slowest {
+                        lines.push((
+                            "slowest part",
+                            format!("day {day} part {part} ({})", output::duration(duration)),
+                        ));
+                    }
+                    if let Some((matched, recorded)) = answers {
+                        lines.push(("answers matched", format!("{matched} of {recorded}")));
+                    }
+                    if let Some(stars) = stars {
+                        lines.push(("stars", stars.to_string()));
+                    }
+
+                    for (label, value) in lines {
+                        println!("{}", output::answer_line(&style, Some(label), &value, None));
+                    }
+                }
+                Output::Json => println!(
+                    "{}",
+                    serde_json::json!({
+                        "days": days,
+                        "parts": parts,
+                        "solved": totals.solved,
+                        "failed": totals.failed,
+                        "duration_ms": totals.duration.as_secs_f64() * 1000.0,
+                        "slowest": totals.slowest.map(|(day, part, duration)| serde_json::json!({
+                            "day": day,
+                            "part": part,
+                            "duration_ms": duration.as_secs_f64() * 1000.0,
+                        })),
+                        "answers_matched": answers.map(|(matched, _)| matched),
+                        "answers_recorded": answers.map(|(_, recorded)| recorded),
+                        "stars": stars,
+                    })
+                ),
+            }
+
+            Ok(())
+        }
+        Outcome::Bench { day, timings } => {
+            for (part, t) in timings {
+                let part = part.number();
+                match cli.output {
+                    Output::Text => println!(
+                        "day {day} part {part}: min {:.1?}, median {:.1?}, mean {:.1?} ({} iterations)",
+                        t.min, t.median, t.mean, t.iterations
+                    ),
+                    Output::Json => println!(
+                        "{}",
+                        serde_json::json!({
+                            "day": day,
+                            "part": part,
+                            "iterations": t.iterations,
+                            "min_ms": t.min.as_secs_f64() * 1000.0,
+                            "median_ms": t.median.as_secs_f64() * 1000.0,
+                            "mean_ms": t.mean.as_secs_f64() * 1000.0,
+                        })
+                    ),
+                }
+            }
+            Ok(())
+        }
+        Outcome::Compare { day, part, runs } => {
+            let part = part.number();
+            let baseline = runs
+                .first()
+                .and_then(|(_, _, timings)| timings.as_ref())
+                .map(|t| t.median);
+
+            for (name, run, timings) in &runs {
+                match cli.output {
+                    Output::Text => {
+                        let detail = match (timings, baseline) {
+                            (Some(t), Some(baseline)) => output::compare_timing(t.median, baseline),
+                            _ => "-".into(),
+                        };
+                        let answer = match &run.answer {
+                            Ok(answer) => answer.clone(),
+                            Err(err) => format!("error: {err:#}"),
+                        };
+                        println!(
+                            "{}",
+                            output::answer_line(&style, Some(name), &answer, Some(&detail))
+                        );
+                    }
+                    Output::Json => {
+                        let mut json = part_json(day, part, run);
+                        json["implementation"] = (*name).into();
+                        if let Some(t) = timings {
+                            json["median_ms"] = (t.median.as_secs_f64() * 1000.0).into();
+                        }
+                        println!("{json}");
+                    }
+                }
+            }
+
+            let failed = runs
+                .iter()
+                .filter(|(_, run, _)| run.answer.is_err())
+                .count();
+            if failed > 0 {
+                return Err(anyhow!("{failed} implementation(s) failed to solve"));
+            }
+
+            let mut answers = runs
+                .iter()
+                .filter_map(|(_, run, _)| run.answer.as_ref().ok());
+            let first = answers.next();
+            if answers.any(|answer| Some(answer) != first) {
+                return Err(anyhow!(
+                    "day {day} part {part} implementations disagree on the answer"
+                ));
+            }
+
+            Ok(())
+        }
+        Outcome::Verify { answers, runs } => {
+            let mut failed = 0;
+
+            for run in &runs {
+                for (part, part_run) in run.parts() {
+                    let expected = answers.get(run.day, part);
+                    let (status, detail) = match (&part_run.answer, expected) {
+                        (Err(err), _) => ("error", format!("{err:#}")),
+                        (Ok(answer), None) => ("unrecorded", format!("got {answer}")),
+                        (Ok(answer), Some(expected)) if answer == expected => {
+                            ("pass", String::new())
+                        }
+                        (Ok(answer), Some(expected)) => {
+                            ("fail", format!("expected {expected}, got {answer}"))
+                        }
+                    };
+
+                    if status == "error" || status == "fail" {
+                        failed += 1;
+                    }
+
+                    match cli.output {
+                        Output::Text => println!(
+                            "{}",
+                            output::verify_line(&style, run.day, part, status, &detail)
+                        ),
+                        Output::Json => println!(
+                            "{}",
+                            serde_json::json!({
+                                "day": run.day,
+                                "part": part,
+                                "status": status,
+                                "expected": expected,
+                                "answer": part_run.answer.as_ref().ok(),
+                                "error": part_run.answer.as_ref().err().map(|err| format!("{err:#}")),
+                            })
+                        ),
+                    }
+                }
+            }
+
+            if failed > 0 {
+                Err(anyhow!(
+                    "{failed} part(s) did not match the recorded answers"
+                ))
+            } else {
+                Ok(())
+            }
+        }
+        Outcome::Message(message) => {
+            match cli.output {
+                Output::Text => println!("{message}"),
+                Output::Json => println!("{}", serde_json::json!({ "message": message })),
+            }
+            Ok(())
+        }
+        Outcome::Submit {
+            day,
+            part,
+            answer,
+            verdict,
+        } => {
+            match cli.output {
+                Output::Text => println!("day {day} part {part}: {answer} is {verdict}"),
+                Output::Json => println!(
+                    "{}",
+                    serde_json::json!({
+                        "day": day,
+                        "part": part,
+                        "answer": answer,
+                        "verdict": verdict.to_string(),
+                    })
+                ),
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Fails if any part of `runs` failed to solve, after they have been reported.
+fn check_runs(runs: &[runner::DayRun]) -> anyhow::Result<()> {
+    let failed = runs
+        .iter()
+        .flat_map(runner::DayRun::parts)
+        .filter(|(_, run)| run.answer.is_err())
+        .count();
+
+    if failed > 0 {
+        Err(anyhow!("{failed} part(s) failed to solve"))
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    /// A Markdown table, for pasting into a README.
+    Markdown,
+    /// `day,part,duration_ms,answer` rows, for tracking timings in a spreadsheet.
+    Csv,
+    /// Like csv, but separated by tabs.
+    Tsv,
+}
+
+#[derive(Copy, Clone, Default, PartialEq, Eq, ValueEnum)]
+enum Output {
+    /// Plain text answers, one per line.
+    #[default]
+    Text,
+    /// One JSON object per solved part.
+    Json,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum Part {
+    /// Solve part one of the puzzle.
+    #[value(alias = "1")]
+    One,
+    /// Solve part two of the puzzle.
+    #[value(alias = "2")]
+    Two,
+}
+
+impl Part {
+    fn number(self) -> u8 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+        }
+    }
+
+    fn run(self, day: &runner::Solution, input: runner::Input) -> runner::PartRun {
+        match self {
+            Self::One => runner::run_part(|input| day.run_part_1(input), input),
+            Self::Two => runner::run_part(|input| day.run_part_2(input), input),
+        }
+    }
+
+    /// Run the part, measuring its heap usage as well with `--memory`. With `--timeout`, the
+    /// CLI exits if the part runs for too long.
+    fn solve(self, n: u8, day: &runner::Solution, input: runner::Input, cli: &Cli) -> Solved {
+        let Some(secs) = cli.timeout else {
+            return self.measure(day, input, cli);
+        };
+
+        let err = error::Kind::Timeout
+            .error(format!("exceeded {secs}s"))
+            .context(PartError {
+                day: n,
+                part: self.number(),
+            });
+        let (output, style) = (cli.output, cli.stderr_style());
+        runner::watchdog(
+            Duration::from_secs(secs),
+            move || exit_with(&err, output, &style),
+            || self.measure(day, input, cli),
+        )
+    }
+
+    fn measure(self, day: &runner::Solution, input: runner::Input, cli: &Cli) -> Solved {
+        if cli.memory {
+            let (run, stats) = memory::track(|| self.run(day, input));
+            Solved {
+                memory: Some(stats),
+                ..Solved::new(self, run)
+            }
+        } else {
+            Solved::new(self, self.run(day, input))
+        }
+    }
+}
+
+/// Solve one or both parts of `day`. A single part streams its input, while solving both parts
+/// reads the input into memory once so that it can be shared between them.
+fn solve(n: u8, day: &runner::Solution, part: Option<Part>, cli: &Cli) -> anyhow::Result<Outcome> {
+    if cli.example {
+        return solve_example(n, day, part, cli);
+    }
+
+    let runs = match part {
+        Some(p) => vec![p.solve(n, day, open_input(cli.input(n).as_deref())?, cli)],
+        None => {
+            let bytes = read_input(cli.input(n).as_deref())?;
+            let parts = if day.has_part_2() {
+                vec![Part::One, Part::Two]
+            } else {
+                vec![Part::One]
+            };
+            parts
+                .into_iter()
+                .map(|p| p.solve(n, day, runner::shared_input(&bytes), cli))
+                .collect()
+        }
+    };
+
+    Ok(Outcome::Day {
+        day: n,
+        labelled: part.is_none(),
+        runs,
+    })
+}
+
+/// Solve one or both parts of `day` `iterations` times against a buffered copy of the input,
+/// keeping the first answer of each part alongside its timings.
+fn repeat(
+    n: u8,
+    day: &runner::Solution,
+    part: Option<Part>,
+    iterations: usize,
+    cli: &Cli,
+) -> anyhow::Result<Outcome> {
+    if cli.example {
+        return Err(anyhow!("--repeat cannot be used with --example"));
+    }
+
+    let bytes = read_input(cli.input(n).as_deref())?;
+
+    let parts = match part {
+        Some(part) => vec![part],
+        None if day.has_part_2() => vec![Part::One, Part::Two],
+        None => vec![Part::One],
+    };
+
+    let runs = parts
+        .into_iter()
+        .map(|p| {
+            let (run, timings) = match p {
+                Part::One => runner::repeat(|input| day.run_part_1(input), &bytes, iterations),
+                Part::Two => runner::repeat(|input| day.run_part_2(input), &bytes, iterations),
+            };
+            Solved {
+                timings,
+                ..Solved::new(p, run)
+            }
+        })
+        .collect();
+
+    Ok(Outcome::Day {
+        day: n,
+        labelled: part.is_none(),
+        runs,
+    })
+}
+
+/// Solve one or both parts of `day` against the examples it registered, failing any part whose
+/// answer does not match the expected one. Parts without an example are skipped unless asked for.
+fn solve_example(
+    n: u8,
+    day: &runner::Solution,
+    part: Option<Part>,
+    cli: &Cli,
+) -> anyhow::Result<Outcome> {
+    let examples: Vec<_> = match part {
+        Some(p) => vec![(
+            p,
+            day.example(p.number())
+                .ok_or(anyhow!("day {n} has no example for part {}", p.number()))?,
+        )],
+        None => [Part::One, Part::Two]
+            .into_iter()
+            .filter_map(|p| day.example(p.number()).map(|example| (p, example)))
+            .collect(),
+    };
+
+    if examples.is_empty() {
+        return Err(anyhow!("day {n} has no examples"));
+    }
+
+    let runs = examples
+        .into_iter()
+        .map(|(p, example)| {
+            let mut solved = p.solve(n, day, Box::new(example.input.as_bytes()), cli);
+            solved.run.answer = solved.run.answer.and_then(|answer| {
+                if answer == example.expected {
+                    Ok(answer)
+                } else {
+                    Err(anyhow!("expected {}, got {answer}", example.expected))
+                }
+            });
+            solved
+        })
+        .collect();
+
+    Ok(Outcome::Day {
+        day: n,
+        labelled: part.is_none(),
+        runs,
+    })
+}
+
+/// Solve every registered day against the inputs in `dir`, for `all` and `report`. Times are
+/// always reported, so `--time` is implied, while `--input` is rejected as each day reads its own
+/// input file.
+fn solve_all(
+    days: &[(u8, runner::Solution)],
+    dir: &Path,
+    parallel: bool,
+    cli: &Cli,
+) -> anyhow::Result<Vec<runner::DayRun>> {
+    if cli.input.is_some() {
+        return Err(anyhow!(
+            "--input cannot be used when solving every day, use --input-dir instead"
+        ));
+    }
+
+    if cli.example {
+        return Err(anyhow!("--example cannot be used when solving every day"));
+    }
+
+    Ok(runner::run_all(days, dir, parallel))
+}
+
+/// Repeatedly run one or both parts of `day` against a buffered copy of the input.
+fn bench(
+    (n, day): &(u8, runner::Solution),
+    part: Option<Part>,
+    iterations: usize,
+    cli: &Cli,
+) -> anyhow::Result<Outcome> {
+    if iterations == 0 {
+        return Err(anyhow!("iterations must be at least 1"));
+    }
+
+    let bytes = read_input(cli.input(*n).as_deref())?;
+
+    let parts = match part {
+        Some(part) => vec![part],
+        None if day.has_part_2() => vec![Part::One, Part::Two],
+        None => vec![Part::One],
+    };
+
+    let timings = parts
+        .into_iter()
+        .map(|p| {
+            let timings = match p {
+                Part::One => runner::bench(|input| day.run_part_1(input), &bytes, iterations),
+                Part::Two => runner::bench(|input| day.run_part_2(input), &bytes, iterations),
+            };
+            timings.map(|t| (p, t)).map_err(|err| {
+                err.context(PartError {
+                    day: *n,
+                    part: p.number(),
+                })
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(Outcome::Bench { day: *n, timings })
+}
+
+/// Run `part` of `day` and each of its registered variants `iterations` times against the same
+/// buffered input.
+fn compare(
+    (n, day): &(u8, runner::Solution),
+    part: Part,
+    iterations: usize,
+    cli: &Cli,
+) -> anyhow::Result<Outcome> {
+    if iterations == 0 {
+        return Err(anyhow!("iterations must be at least 1"));
+    }
+
+    let variants: Vec<_> = day.variants(part.number()).collect();
+    if variants.is_empty() {
+        return Err(anyhow!(
+            "day {n} part {} has no alternative implementations to compare",
+            part.number()
+        ));
+    }
+
+    let bytes = read_input(cli.input(*n).as_deref())?;
+
+    let (run, timings) = match part {
+        Part::One => runner::repeat(|input| day.run_part_1(input), &bytes, iterations),
+        Part::Two => runner::repeat(|input| day.run_part_2(input), &bytes, iterations),
+    };
+
+    let runs = std::iter::once(("default", run, timings))
+        .chain(variants.into_iter().map(|variant| {
+            let (run, timings) = runner::repeat(variant.part_fn, &bytes, iterations);
+            (variant.name, run, timings)
+        }))
+        .collect();
+
+    Ok(Outcome::Compare {
+        day: *n,
+        part,
+        runs,
+    })
+}
+
+/// Re-solve `day` whenever the `--input` file changes. Polls the file's modification time, as no
+/// filesystem notifier is available, and re-executes the CLI when its own binary is rebuilt.
+fn watch(
+    (n, day): &(u8, runner::Solution),
+    part: Option<Part>,
+    cli: &Cli,
+) -> anyhow::Result<Outcome> {
+    fn modified(path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    let path = cli
+        .input
+        .as_deref()
+        .filter(|path| *path != Path::new(CLIPBOARD))
+        .ok_or(anyhow!("watch needs an --input file to watch"))?;
+
+    let exe = std::env::current_exe()?;
+    let exe_modified = modified(&exe);
+    let mut input_modified = None;
+
+    loop {
+        if modified(&exe) != exe_modified {
+            eprintln!("-- {} was rebuilt, restarting", exe.display());
+            restart(&exe)?;
+        }
+
+        let m = modified(path);
+        if m.is_some() && m != input_modified {
+            input_modified = m;
+            eprintln!("-- solving day {n} with {}", path.display());
+            if let Err(err) = solve(*n, day, part, cli).and_then(|outcome| report(outcome, cli)) {
+                eprintln!("{}", output::error_line(&cli.stderr_style(), &err));
+            }
+        }
+
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
+
+#[cfg(unix)]
+fn restart(exe: &Path) -> anyhow::Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    let err = std::process::Command::new(exe)
+        .args(std::env::args_os().skip(1))
+        .exec();
+    Err(anyhow!("could not restart {}: {err}", exe.display()))
+}
+
+#[cfg(not(unix))]
+fn restart(exe: &Path) -> anyhow::Result<()> {
+    Err(anyhow!("{} was rebuilt, run watch again", exe.display()))
+}
+
+/// Solve every day against the inputs in `dir`, summarising the runs along with how many match
+/// the `answers` manifest (if there is one) and the user's star count (if logged in).
+fn stats(
+    days: Vec<(u8, runner::Solution)>,
+    dir: &Path,
+    answers: &Path,
+    cli: &Cli,
+) -> anyhow::Result<Outcome> {
+    let runs = solve_all(&days, dir, false, cli)?;
+
+    let answers = if answers.exists() {
+        let answers = answers::Answers::load(answers)?;
+        let (matched, recorded) = runs
+            .iter()
+            .flat_map(|run| {
+                run.parts()
+                    .map(move |(part, part_run)| (run.day, part, part_run))
+            })
+            .filter_map(|(day, part, part_run)| Some((answers.get(day, part)?, part_run)))
+            .fold((0, 0), |(matched, recorded), (expected, part_run)| {
+                let matches = part_run.answer.as_deref().ok() == Some(expected);
+                (matched + usize::from(matches), recorded + 1)
+            });
+        Some((matched, recorded))
+    } else {
+        None
+    };
+
+    // Stars are a bonus, so a missing session or an unreachable website shouldn't fail the
+    // command.
+    let stars = match aoc::Client::configured() {
+        Ok(client) => client.stars().unwrap_or_else(|err| {
+            eprintln!("{}", output::error_line(&cli.stderr_style(), &err));
+            None
+        }),
+        Err(_) => None,
+    };
+
+    Ok(Outcome::Stats {
+        days: days.len(),
+        parts: days
+            .iter()
+            .map(|(_, day)| if day.has_part_2() { 2 } else { 1 })
+            .sum(),
+        totals: runner::Totals::of(&runs),
+        answers,
+        stars,
+    })
+}
+
+/// Solve one part of `day` against its cached input (or `--input`) and submit the answer.
+fn submit(
+    (n, day): &(u8, runner::Solution),
+    part: Part,
+    dir: &Path,
+    cli: &Cli,
+) -> anyhow::Result<Outcome> {
+    let client = aoc::Client::configured()?;
+
+    let path = cli
+        .input
+        .clone()
+        .unwrap_or_else(|| runner::input_path(dir, *n));
+    let answer = part
+        .run(day, open_input(Some(&path))?)
+        .answer
+        .map_err(|err| {
+            err.context(PartError {
+                day: *n,
+                part: part.number(),
+            })
+        })?;
+
+    let verdict = client.submit(*n, part.number(), &answer)?;
+
+    Ok(Outcome::Submit {
+        day: *n,
+        part: part.number(),
+        answer,
+        verdict,
+    })
+}
+
+/// Store a session cookie in the config file, reading it from stdin when not given.
+fn login(session: Option<&str>) -> anyhow::Result<Outcome> {
+    let session = match session {
+        Some(session) => session.to_string(),
+        None => {
+            eprintln!("paste your adventofcode.com session cookie and press enter:");
+            let mut line = String::new();
+            stdin().read_line(&mut line)?;
+            line
+        }
+    };
+    let session = session.trim();
+
+    if session.is_empty() || !session.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(anyhow!(
+            "session cookie should be a string of letters and digits"
+        ));
+    }
+
+    let path = config::config_path()?;
+    let mut config = config::Config::load_from(&path)?;
+    config.session = Some(session.to_string());
+    config.save_to(&path)?;
+
+    Ok(Outcome::Message(format!(
+        "session saved to {}",
+        path.display()
+    )))
+}
+
+fn logout() -> anyhow::Result<Outcome> {
+    let path = config::config_path()?;
+    let mut config = config::Config::load_from(&path)?;
+
+    if config.session.take().is_none() {
+        return Ok(Outcome::Message("not logged in".into()));
+    }
+    config.save_to(&path)?;
+
+    Ok(Outcome::Message(format!(
+        "session removed from {}",
+        path.display()
+    )))
+}
+
+fn whoami() -> anyhow::Result<Outcome> {
+    aoc::Client::configured()?
+        .whoami()?
+        .map(|user| Outcome::Message(format!("logged in as {user}")))
+        .ok_or(anyhow!(
+            "session is not valid, run `login` with a fresh cookie"
+        ))
+}
+
+/// Solve `day` (or every day) against the inputs in `dir` and compare the answers with those
+/// recorded in the `answers` manifest.
+fn verify(
+    days: Vec<(u8, runner::Solution)>,
+    day: Option<u8>,
+    dir: &Path,
+    answers: &Path,
+) -> anyhow::Result<Outcome> {
+    let answers = answers::Answers::load(answers)?;
+
+    let days: Vec<_> = days
+        .into_iter()
+        .filter(|(n, _)| day.is_none_or(|day| day == *n))
+        .collect();
+    if let (Some(day), true) = (day, days.is_empty()) {
+        return Err(anyhow!("day {day} is not implemented"));
+    }
+
+    Ok(Outcome::Verify {
+        answers,
+        runs: runner::run_all(&days, dir, false),
+    })
+}
+
+fn part_json(day: u8, part: u8, run: &runner::PartRun) -> serde_json::Value {
+    match &run.answer {
+        Ok(answer) => serde_json::json!({
+            "day": day,
+            "part": part,
+            "answer": answer,
+            "duration_ms": run.duration.as_secs_f64() * 1000.0,
+        }),
+        Err(err) => serde_json::json!({
+            "day": day,
+            "part": part,
+            "error": format!("{err:#}"),
+        }),
+    }
+}
+
+/// The `--input` value that reads the puzzle input from the clipboard instead of a file.
+const CLIPBOARD: &str = "clipboard";
+
+fn open_input(path: Option<&Path>) -> anyhow::Result<runner::Input> {
+    match path {
+        Some(path) if path == Path::new(CLIPBOARD) => {
+            Ok(Box::new(Cursor::new(clipboard::paste()?)))
+        }
+        Some(path) => File::open(path)
+            .map(|file| Box::new(BufReader::new(file)) as runner::Input)
+            .map_err(|err| {
+                let message = format!("could not open {}: {err}", path.display());
+                match err.kind() {
+                    std::io::ErrorKind::NotFound => error::Kind::InputNotFound.error(message),
+                    _ => anyhow!(message),
+                }
+            }),
+        None => {
+            if stdin().is_terminal() {
+                eprintln!("-- reading puzzle input from stdin, press {EOF_KEY} to finish...");
+            }
+            Ok(Box::new(stdin().lock()))
+        }
+    }
+}
+
+/// The key that ends typed or pasted input on a terminal.
+#[cfg(windows)]
+const EOF_KEY: &str = "Ctrl-Z then Enter";
+#[cfg(not(windows))]
+const EOF_KEY: &str = "Ctrl-D";
+
+fn read_input(path: Option<&Path>) -> anyhow::Result<Arc<[u8]>> {
+    let mut bytes = vec![];
+    open_input(path)?
+        .read_to_end(&mut bytes)
+        .map_err(|err| match path {
+            Some(path) => anyhow!("could not read {}: {err}", path.display()),
+            None => anyhow!("could not read stdin: {err}"),
+        })?;
+    Ok(bytes.into())
+}
+
 mod gen {
+    /// Registers each day's [Day](adventofcode_2024::day::Day), as returned by its `solution()`.
     #[macro_export]
     macro_rules! days{
-    ($($name:ident: $day:expr),+) => {
-        #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
-        enum Part {
-            /// Solve part one of the puzzle.
-            One,
-            /// Solve part two of the puzzle.
-            Two,
-        }
-        #[derive(Subcommand)]
-        enum Day {
-            $(
-                $name {
-                    /// Which part of the puzzle to solve.
-                    part: Part,
-                },
-            )+
-        }
-
-        impl Day {
-            fn solve(&self) -> anyhow::Result<()> {
-                match self {
-                    $(
-                        Self::$name { part: Part::One } => $day.solve_part_1(),
-                        Self::$name { part: Part::Two } => $day.solve_part_2(),
-                    )+
-                }
-            }
+    ($($n:literal => $day:expr),+) => {
+        /// Every registered day, keyed by its day number.
+        fn registry() -> Vec<(u8, runner::Solution)> {
+            vec![$(($n, $day)),+]
+        }
+
+        /// Look up a registered day by its day number.
+        fn find(day: u8) -> anyhow::Result<(u8, runner::Solution)> {
+            registry()
+                .into_iter()
+                .find(|(n, _)| *n == day)
+                .ok_or_else(|| error::Kind::Unimplemented.error(format!("day {day} is not implemented")))
         }
     }
 }
@@ -0,0 +1,510 @@
+use std::{fmt::Display, str::FromStr, time::Duration};
+
+use anyhow::anyhow;
+
+use crate::{
+    memory::Stats,
+    runner::{DayRun, PartRun, Timings},
+};
+
+const BOLD_GREEN: &str = "\x1b[1;32m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// When to colour text output.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Colour output written to a terminal.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            _ => Err(anyhow!(
+                "color must be one of auto, always or never, found {s:?}"
+            )),
+        }
+    }
+}
+
+impl Display for ColorChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Always => write!(f, "always"),
+            Self::Never => write!(f, "never"),
+        }
+    }
+}
+
+/// How text output is decorated. By default colour is only used when writing to a terminal, and
+/// can be turned off with `--no-color` or the `NO_COLOR` environment variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    color: bool,
+}
+
+impl Style {
+    pub fn plain() -> Self {
+        Self { color: false }
+    }
+
+    pub fn colored() -> Self {
+        Self { color: true }
+    }
+
+    /// Picks a style for a stream, following <https://no-color.org>: a non-empty `NO_COLOR`
+    /// overrides `choice`.
+    pub fn detect(choice: ColorChoice, is_terminal: bool) -> Self {
+        let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+
+        Self {
+            color: !no_color_env
+                && match choice {
+                    ColorChoice::Auto => is_terminal,
+                    ColorChoice::Always => true,
+                    ColorChoice::Never => false,
+                },
+        }
+    }
+
+    fn paint(&self, code: &str, s: &str) -> String {
+        if self.color {
+            format!("{code}{s}{RESET}")
+        } else {
+            s.to_string()
+        }
+    }
+
+    pub fn answer(&self, s: &str) -> String {
+        self.paint(BOLD_GREEN, s)
+    }
+
+    pub fn timing(&self, s: &str) -> String {
+        self.paint(DIM, s)
+    }
+
+    pub fn error(&self, s: &str) -> String {
+        self.paint(RED, s)
+    }
+}
+
+/// Formats a single part's answer, e.g. `part 1: 3749 (12.4ms)`. The label is left out when it
+/// is `None`, and the timing when `timing` is `None`.
+pub fn answer_line(
+    style: &Style,
+    label: Option<&str>,
+    answer: &str,
+    timing: Option<&str>,
+) -> String {
+    let mut line = match label {
+        Some(label) => format!("{label}: {}", style.answer(answer)),
+        None => style.answer(answer),
+    };
+
+    if let Some(timing) = timing {
+        line.push(' ');
+        line.push_str(&style.timing(&format!("({timing})")));
+    }
+
+    line
+}
+
+/// Formats how long a single run took, e.g. `12.4ms`.
+pub fn duration(duration: Duration) -> String {
+    format!("{duration:.1?}")
+}
+
+/// Formats timings over repeated runs, e.g. `best 12.1ms, mean 12.4ms over 10 runs`.
+pub fn repeat_timing(timings: &Timings) -> String {
+    format!(
+        "best {:.1?}, mean {:.1?} over {} runs",
+        timings.min, timings.mean, timings.iterations
+    )
+}
+
+/// Formats a median time relative to a baseline, e.g. `median 3.1ms, 2.50x slower`.
+pub fn compare_timing(median: Duration, baseline: Duration) -> String {
+    let ratio = median.as_secs_f64() / baseline.as_secs_f64();
+
+    if median == baseline {
+        format!("median {median:.1?}")
+    } else if ratio >= 1.0 {
+        format!("median {median:.1?}, {ratio:.2}x slower")
+    } else {
+        format!("median {median:.1?}, {:.2}x faster", 1.0 / ratio)
+    }
+}
+
+/// Formats heap usage, e.g. `peak 1.5 KiB in 12 allocations`.
+pub fn memory(stats: &Stats) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut size = stats.peak_bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    let peak = if unit == 0 {
+        format!("{} B", stats.peak_bytes)
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    };
+
+    format!("peak {peak} in {} allocations", stats.allocations)
+}
+
+/// Formats an error for display, e.g. `Error: could not read input`.
+pub fn error_line(style: &Style, err: &anyhow::Error) -> String {
+    style.error(&format!("Error: {err:#}"))
+}
+
+/// Formats the result of checking a part against its recorded answer, e.g.
+/// `day 6 part 2: fail (expected 1604, got 1603)`.
+pub fn verify_line(style: &Style, day: u8, part: u8, status: &str, detail: &str) -> String {
+    let status = match status {
+        "pass" => style.answer(status),
+        "fail" | "error" => style.error(status),
+        _ => status.to_string(),
+    };
+
+    if detail.is_empty() {
+        format!("day {day} part {part}: {status}")
+    } else {
+        format!("day {day} part {part}: {status} ({detail})")
+    }
+}
+
+/// Renders runs of every day as a text table, one row per day.
+pub fn summary_table(style: &Style, runs: &[DayRun]) -> String {
+    enum Cell {
+        Plain(String),
+        Answer(String),
+        Timing(String),
+        Error(String),
+    }
+
+    impl Cell {
+        fn text(&self) -> &str {
+            match self {
+                Self::Plain(s) | Self::Answer(s) | Self::Timing(s) | Self::Error(s) => s,
+            }
+        }
+    }
+
+    fn cells(run: Option<&PartRun>) -> [Cell; 2] {
+        match run {
+            Some(PartRun {
+                answer: Ok(answer),
+                duration,
+            }) => [
+                Cell::Answer(answer.clone()),
+                Cell::Timing(self::duration(*duration)),
+            ],
+            Some(PartRun {
+                answer: Err(err), ..
+            }) => [
+                Cell::Error(format!("error: {err}")),
+                Cell::Plain("-".into()),
+            ],
+            None => [Cell::Plain("-".into()), Cell::Plain("-".into())],
+        }
+    }
+
+    let header = ["day", "part 1", "time", "part 2", "time"].map(|h| Cell::Plain(h.into()));
+
+    let rows: Vec<_> = runs
+        .iter()
+        .map(|run| {
+            let [a1, t1] = cells(Some(&run.part_1));
+            let [a2, t2] = cells(run.part_2.as_ref());
+            [Cell::Plain(run.day.to_string()), a1, t1, a2, t2]
+        })
+        .collect();
+
+    let widths = rows
+        .iter()
+        .fold(header.each_ref().map(|h| h.text().len()), |mut acc, row| {
+            for (w, cell) in acc.iter_mut().zip(row) {
+                *w = (*w).max(cell.text().len());
+            }
+            acc
+        });
+
+    let mut table = String::new();
+    for row in std::iter::once(&header).chain(rows.iter()) {
+        let last = row.iter().rposition(|cell| !cell.text().is_empty());
+        let line = row
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (cell, w))| {
+                // Pad before styling so that escape codes do not count towards the width, and
+                // leave the last cell unpadded to avoid trailing whitespace.
+                let text = if Some(i) == last {
+                    cell.text().to_string()
+                } else {
+                    format!("{:<w$}", cell.text())
+                };
+                match cell {
+                    Cell::Plain(_) => text,
+                    Cell::Answer(_) => style.answer(&text),
+                    Cell::Timing(_) => style.timing(&text),
+                    Cell::Error(_) => style.error(&text),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        table.push_str(&line);
+        table.push('\n');
+    }
+
+    table
+}
+
+/// Renders runs of every day as a Markdown table. With `redact`, answers are left out so that
+/// only timings are published.
+pub fn markdown_table(runs: &[DayRun], redact: bool) -> String {
+    fn cells(run: Option<&PartRun>, redact: bool) -> Vec<String> {
+        let (answer, time) = match run {
+            Some(PartRun {
+                answer: Ok(answer),
+                duration,
+            }) => (format!("`{answer}`"), self::duration(*duration)),
+            Some(PartRun {
+                answer: Err(err), ..
+            }) => (
+                format!("error: {}", err.to_string().replace('|', "\\|")),
+                "-".into(),
+            ),
+            None => ("-".into(), "-".into()),
+        };
+
+        if redact {
+            vec![time]
+        } else {
+            vec![answer, time]
+        }
+    }
+
+    let header = if redact {
+        "| Day | Part 1 time | Part 2 time |\n|---:|---:|---:|\n"
+    } else {
+        "| Day | Part 1 | Time | Part 2 | Time |\n|---:|---|---:|---|---:|\n"
+    };
+
+    let mut table = header.to_string();
+    for run in runs {
+        let mut row = vec![run.day.to_string()];
+        row.extend(cells(Some(&run.part_1), redact));
+        row.extend(cells(run.part_2.as_ref(), redact));
+        table.push_str(&format!("| {} |\n", row.join(" | ")));
+    }
+
+    table
+}
+
+/// Renders runs of every day as `day,part,duration_ms,answer` rows separated by `separator`,
+/// e.g. `,` for CSV or a tab for TSV, with a header row. Parts that failed have an empty duration
+/// and answer. With `redact`, the answer column is left out.
+pub fn delimited_table(runs: &[DayRun], separator: char, redact: bool) -> String {
+    let field = |value: &str| {
+        if value.contains([separator, '"', '\n']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    };
+
+    let mut header = vec!["day", "part", "duration_ms"];
+    if !redact {
+        header.push("answer");
+    }
+
+    let mut table = header.join(&separator.to_string());
+    table.push('\n');
+
+    for run in runs {
+        for (part, part_run) in run.parts() {
+            let (duration, answer) = match &part_run.answer {
+                Ok(answer) => (
+                    format!("{:.3}", part_run.duration.as_secs_f64() * 1000.0),
+                    field(answer),
+                ),
+                Err(_) => (String::new(), String::new()),
+            };
+
+            let mut row = vec![run.day.to_string(), part.to_string(), duration];
+            if !redact {
+                row.push(answer);
+            }
+
+            table.push_str(&row.join(&separator.to_string()));
+            table.push('\n');
+        }
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn runs() -> Vec<DayRun> {
+        vec![
+            DayRun {
+                day: 1,
+                part_1: PartRun {
+                    answer: Ok("11".into()),
+                    duration: Duration::from_millis(2),
+                },
+                part_2: Some(PartRun {
+                    answer: Ok("31".into()),
+                    duration: Duration::from_millis(3),
+                }),
+            },
+            DayRun {
+                day: 25,
+                part_1: PartRun {
+                    answer: Ok("3".into()),
+                    duration: Duration::from_millis(1),
+                },
+                part_2: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn summary_table_aligns_columns() {
+        assert_eq!(
+            summary_table(&Style::plain(), &runs()),
+            "day  part 1  time   part 2  time
+1    11      2.0ms  31      3.0ms
+25   3       1.0ms  -       -
+"
+        );
+    }
+
+    #[test]
+    fn summary_table_aligns_colored_columns() {
+        let table = summary_table(&Style::colored(), &runs());
+        let stripped = table
+            .replace(BOLD_GREEN, "")
+            .replace(DIM, "")
+            .replace(RESET, "");
+
+        assert_eq!(stripped, summary_table(&Style::plain(), &runs()));
+        assert!(table.contains(&format!("{BOLD_GREEN}11    {RESET}")));
+    }
+
+    #[test]
+    fn answer_line_with_timing() {
+        assert_eq!(
+            answer_line(
+                &Style::colored(),
+                Some("part 1"),
+                "3749",
+                Some(&duration(Duration::from_micros(12_400)))
+            ),
+            format!("part 1: {BOLD_GREEN}3749{RESET} {DIM}(12.4ms){RESET}")
+        );
+        assert_eq!(answer_line(&Style::plain(), None, "3749", None), "3749");
+    }
+
+    #[test]
+    fn markdown_table_with_answers() {
+        assert_eq!(
+            markdown_table(&runs(), false),
+            "| Day | Part 1 | Time | Part 2 | Time |
+|---:|---|---:|---|---:|
+| 1 | `11` | 2.0ms | `31` | 3.0ms |
+| 25 | `3` | 1.0ms | - | - |
+"
+        );
+    }
+
+    #[test]
+    fn markdown_table_redacted() {
+        assert_eq!(
+            markdown_table(&runs(), true),
+            "| Day | Part 1 time | Part 2 time |
+|---:|---:|---:|
+| 1 | 2.0ms | 3.0ms |
+| 25 | 1.0ms | - |
+"
+        );
+    }
+
+    #[test]
+    fn delimited_table_as_csv() {
+        assert_eq!(
+            delimited_table(&runs(), ',', false),
+            "day,part,duration_ms,answer
+1,1,2.000,11
+1,2,3.000,31
+25,1,1.000,3
+"
+        );
+    }
+
+    #[test]
+    fn delimited_table_quotes_fields() {
+        let runs = vec![DayRun {
+            day: 3,
+            part_1: PartRun {
+                answer: Ok("1,2".into()),
+                duration: Duration::from_micros(1500),
+            },
+            part_2: Some(PartRun {
+                answer: Err(anyhow!("failed")),
+                duration: Duration::ZERO,
+            }),
+        }];
+
+        assert_eq!(
+            delimited_table(&runs, ',', false),
+            "day,part,duration_ms,answer
+3,1,1.500,\"1,2\"
+3,2,,
+"
+        );
+        assert_eq!(
+            delimited_table(&runs, '\t', true),
+            "day\tpart\tduration_ms
+3\t1\t1.500
+3\t2\t
+"
+        );
+    }
+
+    #[test]
+    fn memory_uses_binary_units() {
+        assert_eq!(
+            memory(&Stats {
+                peak_bytes: 512,
+                allocations: 3
+            }),
+            "peak 512 B in 3 allocations"
+        );
+        assert_eq!(
+            memory(&Stats {
+                peak_bytes: 1536,
+                allocations: 12
+            }),
+            "peak 1.5 KiB in 12 allocations"
+        );
+    }
+}
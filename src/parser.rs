@@ -1,30 +1,256 @@
 use std::{
     collections::VecDeque,
-    io::{Bytes, Read},
+    fmt::Display,
+    io::{ErrorKind, Read},
+    num::ParseIntError,
+    str::FromStr,
+    thread,
+    time::Duration,
 };
 
+use anyhow::{anyhow, Context};
+use smol_str::{SmolStr, SmolStrBuilder};
+
+use crate::{
+    error::Kind,
+    grid::{Grid2D, Vec2},
+};
+
+mod slice;
+
+pub use slice::SliceParser;
+
 /// A [Parser] instance for working with types implementing [std::io::Read].
 pub type BytesParser<R> = Parser<BytesReader<R>>;
 
 /// A parser over a stream of bytes, where reading each byte can produce an error (e.g. a byte
-/// stream being pulled from some IO source.
-/// Panics when the underlying stream does produce an error.
+/// stream being pulled from some IO source. Bytes are decoded as UTF-8.
+/// When the underlying stream produces an error, the stream is treated as ended and the error is
+/// kept until it is collected with [Parser::check] (or surfaced by [Parser::try_next] and
+/// [Parser::try_peek]).
 /// Provides low-level methods for parsing the byte stream, and higher-level methods for parsing
 /// common lexemes.
 pub struct Parser<S: Iterator<Item = anyhow::Result<u8>>> {
     source: S,
+    /// Characters read from the source but not yet consumed, next character first.
     peeked: VecDeque<char>,
-    peeked_container: String,
+    /// Bytes and newlines read from the source so far, for debug logging.
+    bytes_read: usize,
+    lines_read: usize,
+    /// Whether the source has ended or failed, after which it is not read again.
+    exhausted: bool,
+    /// The error the source stream failed with, until collected by [Parser::check].
+    error: Option<anyhow::Error>,
+    /// Position of the next character to be consumed, which lags behind reading the source when
+    /// characters have been peeked.
+    position: Position,
+    /// Characters consumed since the oldest outstanding checkpoint, so they can be restored.
+    consumed: Vec<char>,
+    checkpoints: usize,
+    /// Recent tokens, when debug logging is enabled.
+    trace: Option<Trace>,
+}
+
+/// The last few tokens a [Parser] consumed, and the methods that consumed them, which are logged
+/// when parsing fails. Only kept when debug logging is enabled, e.g. with `--debug`.
+#[derive(Default)]
+struct Trace {
+    entries: VecDeque<TraceEntry>,
+}
+
+struct TraceEntry {
+    position: Position,
+    method: &'static str,
+    token: String,
+}
+
+impl Trace {
+    const LEN: usize = 32;
+
+    fn push(&mut self, entry: TraceEntry) {
+        if self.entries.len() == Self::LEN {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+}
+
+/// A position in the source stream, counted in characters from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// A point in the stream that a [Parser] can be rewound to, from [Parser::checkpoint].
+#[must_use = "a checkpoint should be restored or committed"]
+#[derive(Debug)]
+pub struct Checkpoint {
+    consumed: usize,
+    position: Position,
 }
 
-/// Utility that maps errors produced by [Bytes](std::io::Bytes) to [anyhow::Error].
-pub struct BytesReader<R: Read>(Bytes<R>);
+/// One of the parsers tried in turn by [Parser::alt].
+pub type Alternative<'a, P, T> = &'a dyn Fn(&mut P) -> Option<T>;
+
+/// A value captured by [Parser::take_pattern].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Capture {
+    /// From a `%d` in the pattern.
+    Integer(i64),
+    /// From a `%w` in the pattern.
+    Word(SmolStr),
+}
+
+/// A set of strings to match against the stream in a single pass, each mapped to a value, for
+/// [Parser::take_keyword]. Stored as a prefix trie.
+pub struct Keywords<T> {
+    nodes: Vec<TrieNode>,
+    values: Vec<T>,
+}
+
+#[derive(Default)]
+struct TrieNode {
+    children: Vec<(char, usize)>,
+    /// The index of the first keyword ending at this node.
+    value: Option<usize>,
+}
+
+impl TrieNode {
+    fn child(&self, c: char) -> Option<usize> {
+        self.children
+            .iter()
+            .find(|(edge, _)| *edge == c)
+            .map(|(_, node)| *node)
+    }
+}
+
+impl<T> Keywords<T> {
+    /// Build a set of keywords. Where several keywords match, the one listed first wins.
+    pub fn new<'a>(keywords: impl IntoIterator<Item = (&'a str, T)>) -> Self {
+        let mut nodes = vec![TrieNode::default()];
+        let mut values = vec![];
+
+        for (keyword, value) in keywords {
+            let mut node = 0;
+            for c in keyword.chars() {
+                node = match nodes[node].child(c) {
+                    Some(child) => child,
+                    None => {
+                        nodes.push(TrieNode::default());
+                        let child = nodes.len() - 1;
+                        nodes[node].children.push((c, child));
+                        child
+                    }
+                };
+            }
+
+            nodes[node].value.get_or_insert(values.len());
+            values.push(value);
+        }
+
+        Self { nodes, values }
+    }
+}
+
+/// Utility that iterates over the bytes of a [Read], mapping its errors to [anyhow::Error]. The
+/// reader is read a block at a time into an internal buffer, rather than a byte at a time.
+///
+/// Not every error from the reader ends the stream: interrupted reads are retried straight away,
+/// reads that would block or time out are retried a few times after a short wait, and an
+/// unexpected EOF is treated as the end of the stream.
+pub struct BytesReader<R: Read> {
+    reader: R,
+    buf: Box<[u8]>,
+    pos: usize,
+    len: usize,
+    /// Transient errors in a row, reset by a successful read.
+    retries: u32,
+}
+
+/// What [BytesReader] does about an error from its reader.
+#[derive(Debug, PartialEq, Eq)]
+enum ReadError {
+    /// Read again straight away.
+    Retry,
+    /// Read again after a short wait, as the error may go away.
+    Transient,
+    /// Treat the error as the end of the stream.
+    End,
+    /// Give up, passing the error on.
+    Fatal,
+}
+
+impl From<ErrorKind> for ReadError {
+    fn from(kind: ErrorKind) -> Self {
+        match kind {
+            ErrorKind::Interrupted => Self::Retry,
+            ErrorKind::WouldBlock | ErrorKind::TimedOut => Self::Transient,
+            ErrorKind::UnexpectedEof => Self::End,
+            _ => Self::Fatal,
+        }
+    }
+}
+
+impl<R: Read> BytesReader<R> {
+    const BUF_SIZE: usize = 16 * 1024;
+    const MAX_RETRIES: u32 = 5;
+    const RETRY_DELAY: Duration = Duration::from_millis(10);
+
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: vec![0; Self::BUF_SIZE].into_boxed_slice(),
+            pos: 0,
+            len: 0,
+            retries: 0,
+        }
+    }
+}
 
 impl<R: Read> Iterator for BytesReader<R> {
     type Item = anyhow::Result<u8>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next().map(|r| r.map_err(|e| e.into()))
+        while self.pos == self.len {
+            let err = match self.reader.read(&mut self.buf) {
+                Ok(0) => return None,
+                Ok(n) => {
+                    (self.pos, self.len, self.retries) = (0, n, 0);
+                    continue;
+                }
+                Err(err) => err,
+            };
+
+            match ReadError::from(err.kind()) {
+                ReadError::Retry => {}
+                ReadError::Transient if self.retries < Self::MAX_RETRIES => {
+                    self.retries += 1;
+                    crate::debug!("retrying read", error = err, attempt = self.retries);
+                    thread::sleep(Self::RETRY_DELAY);
+                }
+                ReadError::End => return None,
+                ReadError::Transient | ReadError::Fatal => return Some(Err(err.into())),
+            }
+        }
+
+        let b = self.buf[self.pos];
+        self.pos += 1;
+        Some(Ok(b))
+    }
+}
+
+impl<S: Iterator<Item = anyhow::Result<u8>>> Drop for Parser<S> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.dump_trace(&"panicked");
+        }
     }
 }
 
@@ -33,7 +259,7 @@ where
     R: Read,
 {
     fn from(value: R) -> Self {
-        Self::new(BytesReader(value.bytes()))
+        Self::new(BytesReader::new(value))
     }
 }
 
@@ -43,7 +269,14 @@ impl<S: Iterator<Item = anyhow::Result<u8>>> Parser<S> {
         Self {
             source,
             peeked: VecDeque::new(),
-            peeked_container: String::with_capacity(8),
+            bytes_read: 0,
+            lines_read: 0,
+            exhausted: false,
+            error: None,
+            position: Position { line: 1, column: 1 },
+            consumed: vec![],
+            checkpoints: 0,
+            trace: crate::log::enabled().then(Trace::default),
         }
     }
 
@@ -63,14 +296,17 @@ impl<S: Iterator<Item = anyhow::Result<u8>>> Parser<S> {
     /// Returns `None` if the next character after whitespace is not a newline chaaracter.
     pub fn take_newline(&mut self) -> Option<()> {
         self.skip_if_eq(' ');
-        self.next_if_eq('\n').and(Some(()))
+        let start = self.position();
+        self.next_if_eq('\n')?;
+        self.trace("take_newline", start, "'\\n'");
+        Some(())
     }
 
     /// Eagerly consumes digit characters from the source stream and parses them into a single
     /// integer value. Integer can also be started with '-' for negatives.
     ///
     /// Consumes all whitespace characters (besides newlines `\n`).
-    /// If the next character in the stream is neither a digit or '-', returns None.
+    /// If the next characters in the stream are not an integer that fits in an `i64`, returns None.
     pub fn next_integer(&mut self) -> Option<i64> {
         self.skip_if_eq(' ');
         self.integer()
@@ -78,21 +314,399 @@ impl<S: Iterator<Item = anyhow::Result<u8>>> Parser<S> {
 
     /// Eagerly consumes digit characters from the source stream and parses them into a single
     /// integer value. Integer can also be started with '-' for negatives.
-    /// If the next character in the stream is neither a digit or '-', returns None.
+    /// If the next character in the stream is neither a digit or '-' followed by a digit, or the
+    /// integer doesn't fit in an `i64`, returns None without consuming anything. Use
+    /// [Parser::try_integer] to find out why.
     ///
     /// Note that the method [Parser::next_integer] exists as a wrapper for this method that also
     /// consumes leading whitespace before the next integer.
     pub fn integer(&mut self) -> Option<i64> {
-        let mut s = self
-            .next_if(|c| c.is_ascii_digit() || c == '-')
-            .map(|c| format!("{c}"))?;
+        self.try_integer().ok().flatten()
+    }
+
+    /// Like [Parser::next_integer], but parses the integer as any integer type `T`, returning an
+    /// error if it is out of range for `T`, e.g. `-1` for a `u32` or `3000000000` for an `i32`.
+    ///
+    /// Consumes all whitespace characters (besides newlines `\n`).
+    /// Returns `Ok(None)` if the next character in the stream does not start an integer.
+    pub fn try_next_integer<T: FromStr<Err = ParseIntError>>(
+        &mut self,
+    ) -> anyhow::Result<Option<T>> {
+        self.skip_if_eq(' ');
+        self.try_integer()
+    }
+
+    /// Like [Parser::integer], but parses the integer as any integer type `T`, returning an error
+    /// if it is out of range for `T`. Nothing is consumed when there is an error, so that the
+    /// parser's [position](Parser::position) is still the start of the integer.
+    pub fn try_integer<T: FromStr<Err = ParseIntError>>(&mut self) -> anyhow::Result<Option<T>> {
+        self.parse_integer(true)
+    }
+
+    #[allow(unused)]
+    /// Like [Parser::next_integer], but only accepts digits, leaving a '-' in the stream. For
+    /// grammars where '-' means something else, e.g. the range `2-4` or the velocity `v=-3`, where
+    /// the sign is parsed separately.
+    ///
+    /// Consumes all whitespace characters (besides newlines `\n`).
+    /// If the next characters in the stream are not digits that fit in a `u64`, returns None.
+    pub fn next_uint(&mut self) -> Option<u64> {
+        self.skip_if_eq(' ');
+        self.parse_integer(false).ok().flatten()
+    }
+
+    fn parse_integer<T: FromStr<Err = ParseIntError>>(
+        &mut self,
+        signed: bool,
+    ) -> anyhow::Result<Option<T>> {
+        let negative = signed && matches!(*self.peek_n(2), ['-', c] if c.is_ascii_digit());
+        if !negative && !self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            return Ok(None);
+        }
 
-        while let Some(c) = self.next_if(|c| c.is_ascii_digit()) {
-            s.push(c)
+        let start = self.position();
+        let checkpoint = self.checkpoint();
+        let mut s = String::new();
+        if negative {
+            s.extend(self.next());
         }
+        s.push_str(&self.take_while(|c| c.is_ascii_digit()));
 
-        // SAFTEY: s only contains valid integer characters.
-        Some(unsafe { s.parse().unwrap_unchecked() })
+        match s.parse() {
+            Ok(n) => {
+                self.commit(checkpoint);
+                self.trace("integer", start, &s);
+                Ok(Some(n))
+            }
+            Err(err) => {
+                self.restore(checkpoint);
+                Err(self.fail(anyhow!(
+                    "could not parse integer {s} at {start} as {}: {err}",
+                    std::any::type_name::<T>()
+                )))
+            }
+        }
+    }
+
+    #[allow(unused)]
+    /// Like [Parser::next_integer], but for integers written in base `radix` (2 to 36), e.g.
+    /// `ff` in base 16. The digits can be preceded by a '-', and then by a prefix conventional for
+    /// the base: `0x` or `#` for 16, `0o` for 8, and `0b` for 2.
+    ///
+    /// Consumes all whitespace characters (besides newlines `\n`).
+    /// If the next characters in the stream are not an integer in that base, returns None without
+    /// consuming them.
+    pub fn next_integer_radix(&mut self, radix: u32) -> Option<i64> {
+        self.skip_if_eq(' ');
+
+        let start = self.position();
+        let checkpoint = self.checkpoint();
+        let negative = self.next_if_eq('-').is_some();
+
+        let peeked = self.peek_n(3);
+        let prefix = match (radix, peeked) {
+            (16, ['#', ..]) => 1,
+            (16, ['0', 'x' | 'X', ..]) | (8, ['0', 'o' | 'O', ..]) | (2, ['0', 'b' | 'B', ..]) => 2,
+            _ => 0,
+        };
+        // Only treat it as a prefix when digits follow, so that e.g. `0xg` parses as `0`.
+        if peeked.get(prefix).is_some_and(|d| d.is_digit(radix)) {
+            self.skip(prefix);
+        }
+
+        let digits = self.take_while(|c| c.is_digit(radix));
+        match i64::from_str_radix(&digits, radix) {
+            Ok(n) if !digits.is_empty() => {
+                self.commit(checkpoint);
+                let n = if negative { -n } else { n };
+                self.trace("next_integer_radix", start, n);
+                Some(n)
+            }
+            _ => {
+                self.restore(checkpoint);
+                None
+            }
+        }
+    }
+
+    #[allow(unused)]
+    /// Parses a hexadecimal integer, e.g. `ff`, `0xff` or `#ff`. See [Parser::next_integer_radix].
+    pub fn next_hex(&mut self) -> Option<i64> {
+        self.next_integer_radix(16)
+    }
+
+    #[allow(unused)]
+    /// Parses a binary integer, e.g. `101` or `0b101`. See [Parser::next_integer_radix].
+    pub fn next_binary(&mut self) -> Option<i64> {
+        self.next_integer_radix(2)
+    }
+
+    #[allow(unused)]
+    /// Eagerly consumes the characters of a floating point number from the source stream and
+    /// parses them into an `f64`. The number can start with '-' or '+', and can have a fractional
+    /// part (`1.5`, `.5`, `1.`) and an exponent (`1e-3`, `2.5E10`).
+    ///
+    /// Consumes all whitespace characters (besides newlines `\n`).
+    /// If the next characters in the stream do not start a number, returns None without consuming
+    /// them.
+    pub fn next_float(&mut self) -> Option<f64> {
+        self.skip_if_eq(' ');
+
+        let start = self.position();
+        let peeked = self.peek_n(3);
+        let unsigned = match peeked.first()? {
+            '-' | '+' => &peeked[1..],
+            _ => peeked,
+        };
+        match unsigned {
+            [c, ..] if c.is_ascii_digit() => {}
+            ['.', c, ..] if c.is_ascii_digit() => {}
+            _ => return None,
+        }
+
+        let mut s = String::new();
+        s.extend(self.next_if(|c| c == '-' || c == '+'));
+        while let Some(c) = self.next_if(|c| c.is_ascii_digit() || c == '.') {
+            s.push(c);
+            if c == '.' {
+                break;
+            }
+        }
+        if s.ends_with('.') {
+            while let Some(c) = self.next_if(|c| c.is_ascii_digit()) {
+                s.push(c);
+            }
+        }
+
+        // Only take an exponent that has digits, so that e.g. `2east` parses as `2`.
+        let has_exponent = match *self.peek_n(3) {
+            ['e' | 'E', c, ..] if c.is_ascii_digit() => true,
+            ['e' | 'E', '-' | '+', c] if c.is_ascii_digit() => true,
+            _ => false,
+        };
+        if has_exponent {
+            s.extend(self.next());
+            s.extend(self.next_if(|c| c == '-' || c == '+'));
+            while let Some(c) = self.next_if(|c| c.is_ascii_digit()) {
+                s.push(c);
+            }
+        }
+
+        let n = s.parse().ok()?;
+        self.trace("next_float", start, &s);
+        Some(n)
+    }
+
+    #[allow(unused)]
+    /// Parses a coordinate pair of integers separated by `separator`, e.g. `3,-4` with `','`.
+    ///
+    /// Consumes all whitespace characters (besides newlines `\n`) before each integer.
+    /// If the next characters in the stream are not a pair, returns None without consuming them.
+    pub fn next_vec2(&mut self, separator: char) -> Option<Vec2<i64>> {
+        self.seq(|p| {
+            let x = p.next_integer()?;
+            p.skip_if_eq(' ');
+            p.next_if_eq(separator)?;
+            Some(Vec2(x, p.next_integer()?))
+        })
+    }
+
+    #[allow(unused)]
+    /// Like [Parser::next_vec2], but for positions that can't be negative, e.g. indexes into a
+    /// [Grid2D]. A '-' before either coordinate doesn't match.
+    pub fn next_vec2_usize(&mut self, separator: char) -> Option<Vec2<usize>> {
+        self.seq(|p| {
+            let x = p.next_uint()?;
+            p.skip_if_eq(' ');
+            p.next_if_eq(separator)?;
+            let y = p.next_uint()?;
+            Some(Vec2(x.try_into().ok()?, y.try_into().ok()?))
+        })
+    }
+
+    #[allow(unused)]
+    /// Parses a field named `name` holding a coordinate pair, e.g. `take_field("v")` against
+    /// `v=3,-3`, for records of fields like `p=0,4 v=3,-3`.
+    ///
+    /// Consumes all whitespace characters (besides newlines `\n`) before the field.
+    /// If the next characters in the stream are not that field, returns None without consuming
+    /// them.
+    pub fn take_field(&mut self, name: &str) -> Option<Vec2<i64>> {
+        self.skip_if_eq(' ');
+        self.seq(|p| {
+            for c in name.chars() {
+                p.next_if_eq(c)?;
+            }
+            p.next_if_eq('=')?;
+            p.next_vec2(',')
+        })
+    }
+
+    #[allow(unused)]
+    /// Eagerly consumes a run of alphanumeric characters, e.g. a wire or register name, returning
+    /// it as a [SmolStr] (which avoids allocating for short words).
+    ///
+    /// Consumes all whitespace characters (besides newlines `\n`).
+    /// If the next character in the stream is not alphanumeric, returns None.
+    pub fn next_word(&mut self) -> Option<SmolStr> {
+        self.skip_if_eq(' ');
+
+        let start = self.position();
+        let mut word = SmolStrBuilder::new();
+        self.take_while_into(&mut word, |c| c.is_alphanumeric())
+            .ok()?;
+        let word = Some(word.finish()).filter(|word| !word.is_empty())?;
+        self.trace("next_word", start, &word);
+        Some(word)
+    }
+
+    /// Parses a list of values separated by `delimiter` up to the end of the line (consuming the
+    /// newline) or stream, e.g. `75,47,61` with `parser.separated(',', |p| p.next_integer())`.
+    /// Whitespace around delimiters is skipped, and a delimiter of `' '` separates values by
+    /// whitespace alone. An empty line gives an empty list.
+    ///
+    /// Returns an error if a value or delimiter is missing, leaving the parser at the offending
+    /// character.
+    pub fn separated<T, F: FnMut(&mut Self) -> Option<T>>(
+        &mut self,
+        delimiter: char,
+        mut f: F,
+    ) -> anyhow::Result<Vec<T>> {
+        let mut values = vec![];
+
+        while self.take_newline().or_else(|| self.eof()).is_none() {
+            if !values.is_empty() && delimiter != ' ' && self.next_if_eq(delimiter).is_none() {
+                return Err(self.unexpected(format!("{delimiter:?}")));
+            }
+
+            self.skip_if_eq(' ');
+            match f(self) {
+                Some(value) => values.push(value),
+                None => return Err(self.unexpected("value")),
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Parses the rest of the stream as a table of integers, one row per line, with the integers in
+    /// each row separated by whitespace. Blank lines are skipped.
+    pub fn int_table(&mut self) -> anyhow::Result<Vec<Vec<i64>>> {
+        let mut rows = vec![];
+        while self.eof().is_none() {
+            let row = self
+                .separated(' ', |p| p.next_integer())
+                .context("line can only contain integers and whitespace")?;
+            if !row.is_empty() {
+                rows.push(row);
+            }
+        }
+
+        self.check()?;
+        Ok(rows)
+    }
+
+    #[allow(unused)]
+    /// Parses a line of the form `key: value`, split at the first `separator`, e.g. `Register A:
+    /// 729` or `Button A: X+94, Y+34` with `':'`. Both halves are trimmed of whitespace, and the
+    /// newline ending the line is consumed.
+    /// If the line has no `separator`, returns None without consuming anything.
+    pub fn key_value(&mut self, separator: char) -> Option<(String, String)> {
+        let start = self.position();
+        let (key, value) = self.seq(|p| {
+            let key = p.take_while(|c| c != separator && c != '\n');
+            p.next_if_eq(separator)?;
+            let value = p.take_until('\n');
+            p.next_if_eq('\n');
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })?;
+
+        self.trace("key_value", start, format_args!("{key:?}: {value:?}"));
+        Some((key, value))
+    }
+
+    #[allow(unused)]
+    /// Like [Parser::key_value], but parses the value as a `T`, e.g. `("Register A", 729)`.
+    /// Returns an error if the value doesn't parse, by which point the line has been consumed.
+    pub fn key_value_as<T>(&mut self, separator: char) -> anyhow::Result<Option<(String, T)>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let start = self.position();
+        let Some((key, value)) = self.key_value(separator) else {
+            return Ok(None);
+        };
+
+        match value.parse() {
+            Ok(value) => Ok(Some((key, value))),
+            Err(err) => Err(self.fail(anyhow!(
+                "could not parse value {value:?} of {key:?} at {start}: {err}"
+            ))),
+        }
+    }
+
+    /// Like [Parser::next_integer], but returns an error saying what was found instead if the next
+    /// characters in the stream are not an integer, e.g. `expected integer at line 3, column 5,
+    /// found 'x'`.
+    pub fn expect_integer(&mut self) -> anyhow::Result<i64> {
+        match self.try_next_integer()? {
+            Some(n) => Ok(n),
+            None => Err(self.unexpected("integer")),
+        }
+    }
+
+    #[allow(unused)]
+    /// Consumes `literal` if the stream continues with it, e.g. `take_exact("Prize: ")`, or
+    /// returns an error quoting the text found in its place.
+    pub fn take_exact(&mut self, literal: &str) -> anyhow::Result<()> {
+        let start = self.position();
+        let len = literal.chars().count();
+        let peeked = self.peek_n(len);
+        if peeked.iter().copied().eq(literal.chars()) {
+            self.skip(len);
+            self.trace("take_exact", start, format_args!("{literal:?}"));
+            return Ok(());
+        }
+
+        let found: String = peeked.iter().collect();
+        let err = if found.is_empty() {
+            anyhow!("expected {literal:?} at {start}, found end of input")
+        } else {
+            anyhow!("expected {literal:?} at {start}, found {found:?}")
+        };
+        Err(self.fail(err))
+    }
+
+    /// Consumes the next character if it is `c`, or returns an error saying what was found instead.
+    pub fn expect_char(&mut self, c: char) -> anyhow::Result<()> {
+        let start = self.position();
+        match self.next_if_eq(c) {
+            Some(_) => {
+                self.trace("expect_char", start, format_args!("{c:?}"));
+                Ok(())
+            }
+            None => Err(self.unexpected(format!("{c:?}"))),
+        }
+    }
+
+    /// Like [Parser::take_newline], but also accepts the end of the stream, so that the last line
+    /// doesn't need a trailing newline. Returns an error saying what was found instead of either.
+    pub fn expect_newline(&mut self) -> anyhow::Result<()> {
+        match self.take_newline().or_else(|| self.eof()) {
+            Some(()) => Ok(()),
+            None => Err(self.unexpected("newline")),
+        }
+    }
+
+    /// An error for when something other than `expected` is next in the stream.
+    fn unexpected(&mut self, expected: impl Display) -> anyhow::Error {
+        let position = self.position();
+        let err = match self.peek() {
+            Some(c) => anyhow!("expected {expected} at {position}, found {c:?}"),
+            None => anyhow!("expected {expected} at {position}, found end of input"),
+        };
+        self.fail(err)
     }
 
     /// Eagerly consume all characters matching arg `c`, stop at the first character that does not
@@ -109,138 +723,647 @@ impl<S: Iterator<Item = anyhow::Result<u8>>> Parser<S> {
     }
 
     #[allow(unused)]
-    /// Skip the next `n` characters.
-    pub fn skip(&mut self, n: usize) {
-        for _ in 0..n {
+    /// Consume characters while function `f` returns `true` for them, returning them as a
+    /// `String`. Stops at the first character that does not match without consuming it.
+    pub fn take_while<F: Fn(char) -> bool>(&mut self, f: F) -> String {
+        let mut s = String::new();
+        while let Some(c) = self.next_if(&f) {
+            s.push(c);
+        }
+        s
+    }
+
+    #[allow(unused)]
+    /// Consume characters up to (but not including) the next `delimiter`, or the end of the
+    /// stream, returning them as a `String`.
+    pub fn take_until(&mut self, delimiter: char) -> String {
+        self.take_while(|c| c != delimiter)
+    }
+
+    /// Like [Parser::take_while], but writes the characters into `buf` rather than allocating a
+    /// new `String`, e.g. to build a `SmolStr`.
+    pub fn take_while_into<F: Fn(char) -> bool>(
+        &mut self,
+        buf: &mut impl std::fmt::Write,
+        f: F,
+    ) -> std::fmt::Result {
+        while let Some(c) = self.next_if(&f) {
+            buf.write_char(c)?;
+        }
+        Ok(())
+    }
+
+    #[allow(unused)]
+    /// Consume everything up to and including the next newline character (`\n`), or up to the end
+    /// of the stream, e.g. to ignore a header line.
+    pub fn skip_line(&mut self) {
+        self.skip_if(|c| c != '\n');
+        self.next_if_eq('\n');
+    }
+
+    #[allow(unused)]
+    /// Skip the next `n` characters.
+    pub fn skip(&mut self, n: usize) {
+        for _ in 0..n {
+            self.next();
+        }
+    }
+
+    /// Consume and return the next character in the stream if the provided function `f` returns
+    /// `true` when passed that character, otherwise returns `None` and does not consume any
+    /// characters from the stream.
+    pub fn next_if<F: Fn(char) -> bool>(&mut self, f: F) -> Option<char> {
+        self.peek()
+            .filter(|peeked| f(*peeked))
+            .and_then(|_| self.next())
+    }
+
+    /// Consume and return the next character in the stream if that character equals `c`, otherwise
+    /// returns `None` and does not consume any characters from the stream.
+    pub fn next_if_eq(&mut self, c: char) -> Option<char> {
+        self.peek()
+            .filter(|peeked| *peeked == c)
+            .and_then(|_| self.next())
+    }
+
+    /// Matches a scanf-like `pattern` against the next characters in the stream, returning the
+    /// values captured by its placeholders if the whole pattern matches, e.g. `mul(%d,%d)`
+    /// against `mul(2,4)` captures the integers 2 and 4. If the pattern doesn't match, nothing is
+    /// consumed.
+    ///
+    /// Placeholders are `%d` for an integer (see [Parser::integer]), `%w` for a run of
+    /// alphanumeric characters, and `%%` for a literal `%`. Every other character must match
+    /// exactly, including whitespace.
+    ///
+    /// Panics if the pattern contains any other placeholder.
+    pub fn take_pattern(&mut self, pattern: &str) -> Option<Vec<Capture>> {
+        let start = self.position();
+        let checkpoint = self.checkpoint();
+        let captures = self.match_pattern(pattern);
+        if let Some(captures) = &captures {
+            self.commit(checkpoint);
+            self.trace("take_pattern", start, format_args!("{captures:?}"));
+        } else {
+            self.restore(checkpoint);
+        }
+        captures
+    }
+
+    fn match_pattern(&mut self, pattern: &str) -> Option<Vec<Capture>> {
+        let mut captures = vec![];
+        let mut chars = pattern.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                self.next_if_eq(c)?;
+                continue;
+            }
+
+            match chars.next() {
+                Some('d') => captures.push(Capture::Integer(self.integer()?)),
+                Some('w') => {
+                    let mut word = SmolStrBuilder::new();
+                    self.take_while_into(&mut word, |c| c.is_alphanumeric())
+                        .ok()?;
+                    let word = word.finish();
+                    if word.is_empty() {
+                        return None;
+                    }
+                    captures.push(Capture::Word(word));
+                }
+                Some('%') => {
+                    self.next_if_eq('%')?;
+                }
+                other => panic!("unsupported placeholder in pattern {pattern:?}: %{other:?}"),
+            }
+        }
+
+        Some(captures)
+    }
+
+    #[allow(unused)]
+    /// Iterates over a list of `&'static str`s and returns the first that matches the next
+    /// characters in the source stream.
+    /// If a match is found, consumes the matching `str`s bytes from tehs tream. Otherwise the
+    /// stream is not advanced (except where some portion of the stream is cached internally).
+    ///
+    /// If there is a need to map the matched strings to values, consider using
+    /// [Parser::take_matching_and]. When matching against the same strings repeatedly, build
+    /// [Keywords] once and use [Parser::take_keyword] instead. To match a single literal, use
+    /// [Parser::take_exact].
+    pub fn take_matching<V: IntoIterator<Item = &'static str>>(
+        &mut self,
+        v: V,
+    ) -> Option<&'static str> {
+        self.take_keyword(&Keywords::new(v.into_iter().map(|s| (s, s))))
+            .copied()
+    }
+
+    #[allow(unused)]
+    /// Iterates over a list of `&'static str` and T pairs, and returns the T for the first string
+    /// that matches the next characters in the source stream.
+    /// If a match is found, consumes the matching `str`s bytes from tehs tream. Otherwise the
+    /// stream is not advanced (except where some portion of the stream is cached internally).
+    pub fn take_matching_and<T, V: IntoIterator<Item = (&'static str, T)>>(
+        &mut self,
+        v: V,
+    ) -> Option<T> {
+        let keywords = Keywords::new(v);
+        let (index, len) = self.match_keyword(&keywords)?;
+        self.skip_keyword("take_matching_and", len);
+        keywords.values.into_iter().nth(index)
+    }
+
+    /// Returns the value of the first of `keywords` that matches the next characters in the
+    /// stream, consuming those characters. This reads each character of the stream once, however
+    /// many keywords there are.
+    pub fn take_keyword<'k, T>(&mut self, keywords: &'k Keywords<T>) -> Option<&'k T> {
+        let (index, len) = self.match_keyword(keywords)?;
+        self.skip_keyword("take_keyword", len);
+        Some(&keywords.values[index])
+    }
+
+    /// Consumes a keyword `len` characters long, which has been matched by `method`.
+    fn skip_keyword(&mut self, method: &'static str, len: usize) {
+        if self.trace.is_some() {
+            let start = self.position();
+            let keyword: String = self.peek_n(len).iter().collect();
+            self.trace(method, start, format_args!("{keyword:?}"));
+        }
+        self.skip(len);
+    }
+
+    /// Walks `keywords` along the next characters in the stream, returning the index of the
+    /// first-listed keyword that matches, along with its length.
+    fn match_keyword<T>(&mut self, keywords: &Keywords<T>) -> Option<(usize, usize)> {
+        let mut node = 0;
+        let mut matched = keywords.nodes[node].value.map(|index| (index, 0));
+
+        for depth in 0.. {
+            let Some(&c) = self.peek_n(depth + 1).get(depth) else {
+                break;
+            };
+            let Some(next) = keywords.nodes[node].child(c) else {
+                break;
+            };
+
+            node = next;
+            if let Some(index) = keywords.nodes[node].value {
+                if matched.is_none_or(|(best, _)| index < best) {
+                    matched = Some((index, depth + 1));
+                }
+            }
+        }
+
+        matched
+    }
+
+    #[allow(unused)]
+    /// Load the next `n` characters from the stream into a buffer and return them, or fewer if
+    /// the stream ends first. The buffer is cached and consumed prior to reading anymore values
+    /// from the stream, so repeated lookahead does not allocate.
+    pub fn peek_n(&mut self, n: usize) -> &[char] {
+        while self.peeked.len() < n {
+            match self.take_next() {
+                Some(c) => self.peeked.push_back(c),
+                None => break,
+            }
+        }
+
+        let peeked = self.peeked.make_contiguous();
+        &peeked[..n.min(peeked.len())]
+    }
+
+    #[allow(unused)]
+    /// Loads the rest of the current line into the lookahead buffer and returns it, without the
+    /// `\n` ending it, and without consuming anything. For deciding how to parse a line from its
+    /// whole contents, e.g. whether it contains a '|'.
+    pub fn peek_line(&mut self) -> &[char] {
+        let mut len = self.peeked.iter().position(|c| *c == '\n');
+        while len.is_none() {
+            match self.take_next() {
+                Some(c) => self.peeked.push_back(c),
+                None => break,
+            }
+            if self.peeked.back() == Some(&'\n') {
+                len = Some(self.peeked.len() - 1);
+            }
+        }
+
+        let peeked = self.peeked.make_contiguous();
+        &peeked[..len.unwrap_or(peeked.len())]
+    }
+
+    /// Returns the next character in the stream without consuming that value. Repeated calls will
+    /// return the same value without advancing the stream.
+    ///
+    /// Note that this function _will_ advance the underlying stream by one when loading a
+    /// previously un-peeked character.
+    pub fn peek(&mut self) -> Option<char> {
+        self.peeked.front().copied().or_else(|| {
+            let next = self.take_next()?;
+            self.peeked.push_back(next);
+            Some(next)
+        })
+    }
+
+    /// Advance the underlying stream by one and return the next character. Returns `None` when the
+    /// stream ends.
+    #[allow(unused, clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<char> {
+        let next = self.peeked.pop_front().or_else(|| self.take_next())?;
+        if self.checkpoints > 0 {
+            self.consumed.push(next);
+        }
+        if next == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        }
+        Some(next)
+    }
+
+    /// Marks the current point in the stream, so that the parser can try parsing something and
+    /// [restore](Parser::restore) to here if it doesn't match. Characters consumed after a
+    /// checkpoint are buffered until it is restored or [committed](Parser::commit), so every
+    /// checkpoint must be one or the other. Checkpoints can be nested.
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        self.checkpoints += 1;
+        Checkpoint {
+            consumed: self.consumed.len(),
+            position: self.position,
+        }
+    }
+
+    /// Rewinds the parser to `checkpoint`, so that everything consumed since will be read again.
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        for c in self.consumed.drain(checkpoint.consumed..).rev() {
+            self.peeked.push_front(c);
+        }
+        let from = self.position;
+        self.trace("restore", checkpoint.position, format_args!("from {from}"));
+        self.position = checkpoint.position;
+        self.release();
+    }
+
+    /// Keeps everything consumed since `checkpoint`, which can no longer be restored.
+    pub fn commit(&mut self, _checkpoint: Checkpoint) {
+        self.release();
+    }
+
+    fn release(&mut self) {
+        self.checkpoints -= 1;
+        if self.checkpoints == 0 {
+            self.consumed.clear();
+        }
+    }
+
+    /// Runs `f` as a sequence of steps that must all match, chaining them with `?`. If any step
+    /// returns `None`, the parser is rewound to where it was before `f` ran.
+    ///
+    /// ```text
+    /// let ordering = parser.seq(|p| {
+    ///     let left = p.next_integer()?;
+    ///     p.next_if_eq('|')?;
+    ///     Some((left, p.next_integer()?))
+    /// });
+    /// ```
+    pub fn seq<T>(&mut self, f: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        let checkpoint = self.checkpoint();
+        let value = f(self);
+        match value {
+            Some(_) => self.commit(checkpoint),
+            None => self.restore(checkpoint),
+        }
+        value
+    }
+
+    /// Tries each of `parsers` in order, returning the value of the first that matches. Each
+    /// failed attempt is rewound before the next is tried.
+    #[allow(unused)]
+    pub fn alt<T, const N: usize>(&mut self, parsers: [Alternative<'_, Self, T>; N]) -> Option<T> {
+        parsers.into_iter().find_map(|parser| self.seq(parser))
+    }
+
+    /// Parses something that may be absent, consuming nothing if it doesn't match. The same as
+    /// [Parser::seq], but reads better for the optional parts of a sequence, where the result is
+    /// used as an `Option` rather than with `?`.
+    #[allow(unused)]
+    pub fn opt<T>(&mut self, f: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        self.seq(f)
+    }
+
+    /// Parses `f` as many times as it matches, possibly none, leaving the stream at the start of
+    /// the first failed attempt.
+    #[allow(unused)]
+    pub fn many<T>(&mut self, f: impl Fn(&mut Self) -> Option<T>) -> Vec<T> {
+        let mut values = Vec::new();
+        while let Some(value) = self.seq(&f) {
+            values.push(value);
+        }
+        values
+    }
+
+    /// The position of the next character to be consumed, for pointing at problems in error
+    /// messages, e.g. `expected integer at line 42, column 7`.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Like [Parser::next], but returns an error if the source stream has failed rather than
+    /// treating the failure as the end of the stream.
+    #[allow(unused)]
+    pub fn try_next(&mut self) -> anyhow::Result<Option<char>> {
+        let next = self.next();
+        self.check().map(|_| next)
+    }
+
+    /// Like [Parser::peek], but returns an error if the source stream has failed rather than
+    /// treating the failure as the end of the stream.
+    #[allow(unused)]
+    pub fn try_peek(&mut self) -> anyhow::Result<Option<char>> {
+        let peeked = self.peek();
+        self.check().map(|_| peeked)
+    }
+
+    /// Returns the error the source stream failed with, if it has. A failed stream looks like it
+    /// has ended to every other method, so iterators built on a parser should call this before
+    /// reporting the end of their own items.
+    pub fn check(&mut self) -> anyhow::Result<()> {
+        match self.error.take() {
+            Some(err) => {
+                self.dump_trace(&err);
+                Err(err.context("could not read input"))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Records that `method` consumed `token`, which started at `start`, if tracing.
+    fn trace(&mut self, method: &'static str, start: Position, token: impl Display) {
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEntry {
+                position: start,
+                method,
+                token: token.to_string(),
+            });
+        }
+    }
+
+    /// Logs the recent tokens, if tracing, before returning `err` tagged as a [Kind::Parse] error.
+    fn fail(&self, err: anyhow::Error) -> anyhow::Error {
+        self.dump_trace(&err);
+        Kind::Parse.wrap(err)
+    }
+
+    fn dump_trace(&self, reason: &dyn Display) {
+        let Some(trace) = &self.trace else {
+            return;
+        };
+
+        crate::debug!(
+            "parsing failed, recent tokens follow",
+            reason = reason,
+            tokens = trace.entries.len()
+        );
+        for entry in &trace.entries {
+            crate::debug!(
+                "token",
+                at = entry.position,
+                method = entry.method,
+                token = entry.token
+            );
+        }
+    }
+
+    /// Parses a rectangular block of characters into a grid, one row per line, up to a blank line
+    /// or the end of the stream. The blank line is consumed too, leaving the parser at whatever
+    /// follows the grid, e.g. a list of moves after a map.
+    ///
+    /// Returns an error if the rows are not all the same width, or there are none.
+    pub fn grid(&mut self) -> anyhow::Result<Grid2D<char>> {
+        let start = self.position();
+        let mut chars = String::new();
+        let mut width = None;
+
+        loop {
+            let line = self.position().line;
+            let row = self.take_until('\n');
+            self.next_if_eq('\n');
+            if row.is_empty() {
+                break;
+            }
+
+            let row_width = row.chars().count();
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    return Err(self.fail(anyhow!(
+                        "grid rows must all be {width} wide, but line {line} is {row_width}"
+                    )))
+                }
+                Some(_) => chars.push('\n'),
+            }
+            chars.push_str(&row);
+        }
+
+        self.check()?;
+        if width.is_none() {
+            return Err(self.fail(anyhow!("expected a grid at {start}")));
+        }
+
+        Ok(Grid2D::from(chars.chars()))
+    }
+
+    #[allow(unused)]
+    /// Iterates over the remaining lines of the stream, without their `\n` (or `\r\n`) line
+    /// endings. A final line without a line ending is still returned, but a line ending at the end
+    /// of the stream does not start another, empty, line.
+    /// Errors from reading the source stream are passed on, ending the iterator.
+    pub fn lines(&mut self) -> impl Iterator<Item = anyhow::Result<String>> + '_ {
+        let mut failed = false;
+        std::iter::from_fn(move || {
+            if failed {
+                return None;
+            }
+            if self.peek().is_none() {
+                return self.check().err().map(|err| {
+                    failed = true;
+                    Err(err)
+                });
+            }
+
+            let mut line = self.take_until('\n');
+            self.next_if_eq('\n');
+            if line.ends_with('\r') {
+                line.pop();
+            }
+            Some(Ok(line))
+        })
+    }
+
+    #[allow(unused)]
+    /// Consumes and returns everything left in the stream, e.g. a free-form tail after a
+    /// structured header. Returns an error if the source stream fails.
+    pub fn rest(&mut self) -> anyhow::Result<String> {
+        let rest = self.take_while(|_| true);
+        self.check()?;
+        Ok(rest)
+    }
+
+    #[allow(unused)]
+    /// Like [Parser::rest], but returns the bytes of the stream as they are, without decoding them
+    /// as UTF-8, so a tail that isn't text can be read too.
+    pub fn rest_bytes(&mut self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = vec![];
+        while let Some(c) = self.peeked.front().copied() {
             self.next();
+            bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        }
+        while let Some(b) = self.take_byte() {
+            bytes.push(b);
         }
-    }
 
-    /// Consume and return the next character in the stream if the provided function `f` returns
-    /// `true` when passed that character, otherwise returns `None` and does not consume any
-    /// characters from the stream.
-    pub fn next_if<F: Fn(char) -> bool>(&mut self, f: F) -> Option<char> {
-        self.peek()
-            .filter(|peeked| f(*peeked))
-            .and_then(|_| self.peeked.pop_back())
+        self.check()?;
+        Ok(bytes)
     }
 
-    /// Consume and return the next character in the stream if that character equals `c`, otherwise
-    /// returns `None` and does not consume any characters from the stream.
-    pub fn next_if_eq(&mut self, c: char) -> Option<char> {
-        self.peek()
-            .filter(|peeked| *peeked == c)
-            .and_then(|_| self.peeked.pop_back())
-    }
+    /// Iterates over the remaining blocks of lines, which are separated by blank lines, e.g. the
+    /// page ordering rules and then the updates in day 5. Each section is buffered into a
+    /// `String`, with every line ending in `\n`, ready to be parsed with a [SliceParser]. Blank
+    /// lines at the start or end of the stream, or several in a row, don't give empty sections.
+    /// Errors from reading the source stream are passed on, ending the iterator.
+    pub fn sections(&mut self) -> impl Iterator<Item = anyhow::Result<String>> + '_ {
+        let mut failed = false;
+        std::iter::from_fn(move || {
+            if failed {
+                return None;
+            }
 
-    #[allow(unused)]
-    /// Iterates over a list of `&'static str`s and returns the first that matches the next
-    /// characters in the source stream.
-    /// If a match is found, consumes the matching `str`s bytes from tehs tream. Otherwise the
-    /// stream is not advanced (except where some portion of the stream is cached internally).
-    ///
-    /// If there is a need to map the matched strings to values, consider using
-    /// [Parser::take_matching_and].
-    pub fn take_matching<V: IntoIterator<Item = &'static str>>(
-        &mut self,
-        v: V,
-    ) -> Option<&'static str> {
-        v.into_iter().find_map(|s| {
-            let n = s.as_bytes().iter().try_fold(0usize, |i, b| {
-                if self.peek_n(i + 1).as_bytes().get(i) == Some(b) {
-                    Some(i + 1)
-                } else {
-                    None
+            let mut section = String::new();
+            loop {
+                let line = self.take_until('\n');
+                let ended = self.next_if_eq('\n').is_none();
+                if !line.trim().is_empty() {
+                    section.push_str(&line);
+                    section.push('\n');
+                } else if !section.is_empty() {
+                    break;
+                }
+                if ended {
+                    break;
                 }
-            })?;
-            self.skip(n);
-            Some(s)
+            }
+
+            if let Err(err) = self.check() {
+                failed = true;
+                return Some(Err(err));
+            }
+            if section.is_empty() {
+                return None;
+            }
+            Some(Ok(section))
         })
     }
 
     #[allow(unused)]
-    /// Iterates over a list of `&'static str` and T pairs, and returns the T for the first string
-    /// that matches the next characters in the source stream.
-    /// If a match is found, consumes the matching `str`s bytes from tehs tream. Otherwise the
-    /// stream is not advanced (except where some portion of the stream is cached internally).
-    pub fn take_matching_and<T, V: IntoIterator<Item = (&'static str, T)>>(
-        &mut self,
-        v: V,
-    ) -> Option<T> {
-        v.into_iter().find_map(|(s, t)| {
-            let n = s.as_bytes().iter().try_fold(0usize, |i, b| {
-                if self.peek_n(i + 1).as_bytes().get(i) == Some(b) {
-                    Some(i + 1)
-                } else {
-                    None
-                }
-            })?;
-            self.skip(n);
-            Some(t)
+    /// Converts the parser into an iterator of `char` values, including any already peeked.
+    /// Errors from reading the source stream are passed on, ending the iterator.
+    pub fn chars(mut self) -> impl Iterator<Item = anyhow::Result<char>> {
+        let mut failed = false;
+        std::iter::from_fn(move || {
+            if failed {
+                return None;
+            }
+            match self.next() {
+                Some(c) => Some(Ok(c)),
+                None => self.check().err().map(|err| {
+                    failed = true;
+                    Err(err)
+                }),
+            }
         })
     }
 
-    #[allow(unused)]
-    /// Load the next `n` characters from the stream into a buffer and return them. The buffer is
-    /// cached and consumed prior to reading anymore values from the stream.
-    pub fn peek_n(&mut self, n: usize) -> &str {
-        if n > self.peeked.len() {
-            for _ in 0..(n - self.peeked.len()) {
-                if let Some(c) = self.take_next() {
-                    self.peeked.push_front(c);
-                } else {
-                    break;
-                }
+    /// Reads the next character from the source, decoding it from UTF-8. An invalid sequence is
+    /// treated like a read error, ending the stream.
+    fn take_next(&mut self) -> Option<char> {
+        let lead = self.take_byte()?;
+        let (len, bits) = match lead {
+            0x00..=0x7f => return Some(lead.into()),
+            0xc2..=0xdf => (2, lead & 0x1f),
+            0xe0..=0xef => (3, lead & 0x0f),
+            0xf0..=0xf4 => (4, lead & 0x07),
+            _ => return self.invalid_utf8(),
+        };
+
+        let mut code = u32::from(bits);
+        for _ in 1..len {
+            match self.take_byte() {
+                Some(b) if b & 0xc0 == 0x80 => code = (code << 6) | u32::from(b & 0x3f),
+                _ => return self.invalid_utf8(),
             }
         }
 
-        self.peeked_container.clear();
-        self.peeked_container
-            .extend(self.peeked.iter().rev().take(n));
-
-        &self.peeked_container
-    }
-
-    /// Returns the next character in the stream without consuming that value. Repeated calls will
-    /// return the same value without advancing the stream.
-    ///
-    /// Note that this function _will_ advance the underlying stream by one when loading a
-    /// previously un-peeked character.
-    pub fn peek(&mut self) -> Option<char> {
-        self.peeked.back().copied().or_else(|| {
-            let next = self.take_next()?;
-            self.peeked.push_front(next);
-            Some(next)
-        })
+        // Reject overlong encodings, which have a shorter valid form, as well as surrogates and
+        // code points past the end of unicode.
+        let shortest = match len {
+            2 => 0x80,
+            3 => 0x800,
+            _ => 0x10000,
+        };
+        match char::from_u32(code).filter(|_| code >= shortest) {
+            Some(c) => Some(c),
+            None => self.invalid_utf8(),
+        }
     }
 
-    /// Advance the underlying stream by one and return the next character. Returns `None` when the
-    /// stream ends.
-    #[allow(unused)]
-    pub fn next(&mut self) -> Option<char> {
-        self.peeked.pop_back().or_else(|| self.take_next())
+    fn invalid_utf8(&mut self) -> Option<char> {
+        self.exhausted = true;
+        self.error.get_or_insert_with(|| {
+            Kind::Parse.error(format!("invalid UTF-8 at byte {}", self.bytes_read))
+        });
+        None
     }
 
-    /// Converts the parser into an iterator of `char` values. Any underlying IO errors from
-    /// reading the source stream are converted into panics.
-    pub fn chars(self) -> impl Iterator<Item = char> {
-        self.source.map(|b| match b {
-            Ok(b) => b.into(),
-            Err(err) => panic!("source stream produced an error: {}", err),
-        })
-    }
+    fn take_byte(&mut self) -> Option<u8> {
+        if self.exhausted {
+            return None;
+        }
 
-    fn take_next(&mut self) -> Option<char> {
-        self.source.next().map(|b| match b {
-            Ok(b) => b.into(),
-            Err(err) => panic!("source stream produced an error: {}", err),
-        })
+        match self.source.next() {
+            Some(Ok(b)) => {
+                self.bytes_read += 1;
+                if b == b'\n' {
+                    self.lines_read += 1;
+                }
+                Some(b)
+            }
+            Some(Err(err)) => {
+                self.exhausted = true;
+                self.error.get_or_insert(err);
+                None
+            }
+            None => {
+                self.exhausted = true;
+                crate::debug!(
+                    "reached end of input",
+                    bytes = self.bytes_read,
+                    lines = self.lines_read
+                );
+                None
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::Parser;
+    use std::io::{ErrorKind, Read};
+
+    use super::{BytesReader, Capture, Keywords, Parser, Position, Trace};
+    use crate::grid::Vec2;
 
     macro_rules! parser_for {
         ($e:expr) => {{
@@ -249,6 +1372,75 @@ mod test {
         }};
     }
 
+    #[test]
+    fn bytes_reader_reads_past_its_buffer() {
+        let bytes: Vec<u8> = (0..40_000).map(|i| (i % 251) as u8).collect();
+
+        let read: Vec<u8> = BytesReader::new(&bytes[..])
+            .collect::<anyhow::Result<_>>()
+            .expect("reading a slice to succeed");
+
+        assert_eq!(read, bytes);
+    }
+
+    /// A reader that fails with each of `errors` in turn before reading `bytes`.
+    struct Flaky {
+        errors: Vec<ErrorKind>,
+        bytes: &'static [u8],
+    }
+
+    impl Read for Flaky {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if !self.errors.is_empty() {
+                return Err(self.errors.remove(0).into());
+            }
+            self.bytes.read(buf)
+        }
+    }
+
+    #[test]
+    fn bytes_reader_retries_transient_errors() {
+        let reader = Flaky {
+            errors: vec![
+                ErrorKind::Interrupted,
+                ErrorKind::WouldBlock,
+                ErrorKind::TimedOut,
+            ],
+            bytes: b"ok",
+        };
+
+        let read: Vec<u8> = BytesReader::new(reader)
+            .collect::<anyhow::Result<_>>()
+            .expect("transient errors to be retried");
+
+        assert_eq!(read, b"ok");
+    }
+
+    #[test]
+    fn bytes_reader_classifies_errors() {
+        let reader = Flaky {
+            errors: vec![ErrorKind::UnexpectedEof],
+            bytes: b"unread",
+        };
+        assert_eq!(BytesReader::new(reader).count(), 0);
+
+        let reader = Flaky {
+            errors: vec![ErrorKind::WouldBlock; 10],
+            bytes: b"unread",
+        };
+        assert!(BytesReader::new(reader).any(|b| b.is_err()));
+
+        let reader = Flaky {
+            errors: vec![ErrorKind::PermissionDenied],
+            bytes: b"unread",
+        };
+        let mut bytes = BytesReader::new(reader);
+        assert_eq!(
+            bytes.next().map(|b| b.map_err(|err| err.to_string())),
+            Some(Err("permission denied".into()))
+        );
+    }
+
     #[test]
     fn parser_takes_next_char_until_eof() {
         let mut parser = parser_for!("abc");
@@ -361,6 +1553,610 @@ mod test {
         assert_eq!(parser.next_integer(), None);
     }
 
+    #[test]
+    fn parser_does_not_parse_lone_minus_as_integer() {
+        let mut parser = parser_for!("- -");
+
+        assert_eq!(parser.next_integer(), None);
+        assert_eq!(parser.next(), Some('-'));
+        assert_eq!(parser.next_integer(), None);
+        assert_eq!(parser.next(), Some('-'));
+    }
+
+    #[test]
+    fn parser_parses_unsigned_integers() {
+        let mut parser = parser_for!("2-4 v=-3 18446744073709551615");
+
+        assert_eq!(parser.next_uint(), Some(2));
+        assert_eq!(parser.next_uint(), None);
+        assert_eq!(parser.next(), Some('-'));
+        assert_eq!(parser.next_uint(), Some(4));
+        parser.skip(3);
+        assert_eq!(parser.next_uint(), None);
+        assert_eq!(parser.next_integer(), Some(-3));
+        assert_eq!(parser.next_uint(), Some(u64::MAX));
+        assert_eq!(parser.next_uint(), None);
+    }
+
+    #[test]
+    fn parser_checks_integer_range() {
+        let mut parser = parser_for!("99999999999999999999 3000000000 -1 255");
+
+        assert_eq!(parser.next_integer(), None);
+        assert_eq!(parser.position(), Position { line: 1, column: 1 });
+        assert_eq!(
+            parser.try_integer::<i64>().map_err(|err| err.to_string()),
+            Err(
+                "could not parse integer 99999999999999999999 at line 1, column 1 as i64: \
+                 number too large to fit in target type"
+                    .into()
+            )
+        );
+        assert_eq!(
+            parser.try_next_integer::<u128>().ok(),
+            Some(Some(99999999999999999999))
+        );
+
+        assert!(parser.try_next_integer::<i32>().is_err());
+        assert_eq!(
+            parser.try_next_integer::<u32>().ok(),
+            Some(Some(3000000000))
+        );
+        assert!(parser.try_next_integer::<u8>().is_err());
+        assert_eq!(parser.try_next_integer::<i8>().ok(), Some(Some(-1)));
+        assert_eq!(parser.try_next_integer::<u8>().ok(), Some(Some(255)));
+        assert_eq!(parser.try_next_integer::<u8>().ok(), Some(None));
+    }
+
+    #[test]
+    fn parser_parses_floats() {
+        let mut parser = parser_for!("1.5 -2 +0.25 .5 3. 1e3 -2.5E-2 6.02e+23");
+
+        assert_eq!(parser.next_float(), Some(1.5));
+        assert_eq!(parser.next_float(), Some(-2.0));
+        assert_eq!(parser.next_float(), Some(0.25));
+        assert_eq!(parser.next_float(), Some(0.5));
+        assert_eq!(parser.next_float(), Some(3.0));
+        assert_eq!(parser.next_float(), Some(1000.0));
+        assert_eq!(parser.next_float(), Some(-0.025));
+        assert_eq!(parser.next_float(), Some(6.02e23));
+        assert_eq!(parser.next_float(), None);
+    }
+
+    #[test]
+    fn parser_float_leaves_non_numbers() {
+        let mut parser = parser_for!("- 2east");
+
+        assert_eq!(parser.next_float(), None);
+        assert_eq!(parser.next(), Some('-'));
+        assert_eq!(parser.next_float(), Some(2.0));
+        assert_eq!(parser.next(), Some('e'));
+    }
+
+    #[test]
+    fn parser_keeps_source_error_until_checked() {
+        let source = [Ok(b'1'), Err(anyhow::anyhow!("broken pipe")), Ok(b'2')].into_iter();
+        let mut parser = Parser::new(source);
+
+        assert_eq!(parser.next_integer(), Some(1));
+        assert_eq!(parser.next_integer(), None);
+        assert_eq!(
+            parser.check().map_err(|err| format!("{err:#}")),
+            Err("could not read input: broken pipe".into())
+        );
+        assert!(parser.check().is_ok());
+        assert_eq!(parser.next(), None);
+    }
+
+    #[test]
+    fn parser_try_next_surfaces_source_error() {
+        let source = [Ok(b'a'), Err(anyhow::anyhow!("broken pipe"))].into_iter();
+        let mut parser = Parser::new(source);
+
+        assert_eq!(parser.try_peek().ok(), Some(Some('a')));
+        assert_eq!(parser.try_next().ok(), Some(Some('a')));
+        assert!(parser.try_next().is_err());
+    }
+
+    #[test]
+    fn parser_chars_passes_on_source_error() {
+        let source = [Ok(b'a'), Err(anyhow::anyhow!("broken pipe")), Ok(b'b')].into_iter();
+        let chars: Vec<_> = Parser::new(source)
+            .chars()
+            .map(|c| c.map_err(|err| err.root_cause().to_string()))
+            .collect();
+
+        assert_eq!(chars, [Ok('a'), Err("broken pipe".into())]);
+    }
+
+    #[test]
+    fn parser_tracks_position_of_next_char() {
+        let mut parser = parser_for!("12 a\nb");
+
+        assert_eq!(parser.position(), Position { line: 1, column: 1 });
+        assert_eq!(parser.next_integer(), Some(12));
+        assert_eq!(parser.peek_n(3), [' ', 'a', '\n']);
+        assert_eq!(parser.position(), Position { line: 1, column: 3 });
+        parser.skip(2);
+        assert_eq!(parser.take_newline(), Some(()));
+        assert_eq!(parser.position(), Position { line: 2, column: 1 });
+        assert_eq!(parser.position().to_string(), "line 2, column 1");
+    }
+
+    #[test]
+    fn parser_restores_checkpoint() {
+        let mut parser = parser_for!("12|x\n");
+
+        let checkpoint = parser.checkpoint();
+        assert_eq!(parser.next_integer(), Some(12));
+        assert_eq!(parser.next_if_eq('|'), Some('|'));
+        assert_eq!(parser.next_integer(), None);
+        parser.restore(checkpoint);
+
+        assert_eq!(parser.position(), Position { line: 1, column: 1 });
+        assert_eq!(parser.peek_n(5), ['1', '2', '|', 'x', '\n']);
+        assert_eq!(parser.next_integer(), Some(12));
+    }
+
+    #[test]
+    fn parser_restores_nested_checkpoints() {
+        let mut parser = parser_for!("abcd");
+
+        let outer = parser.checkpoint();
+        assert_eq!(parser.next(), Some('a'));
+        let inner = parser.checkpoint();
+        assert_eq!(parser.next(), Some('b'));
+        assert_eq!(parser.next(), Some('c'));
+        parser.restore(inner);
+        assert_eq!(parser.next(), Some('b'));
+        parser.restore(outer);
+        assert_eq!(parser.next(), Some('a'));
+
+        let checkpoint = parser.checkpoint();
+        assert_eq!(parser.next(), Some('b'));
+        parser.commit(checkpoint);
+        assert_eq!(parser.next(), Some('c'));
+    }
+
+    #[test]
+    fn parser_combinators_backtrack() {
+        let mut parser = parser_for!("12|34,12-34 5 6 7x");
+
+        let ordering = |p: &mut Parser<_>| {
+            let left = p.next_integer()?;
+            p.next_if_eq('|')?;
+            Some((left, p.next_integer()?))
+        };
+        assert_eq!(parser.seq(ordering), Some((12, 34)));
+        assert_eq!(parser.seq(ordering), None);
+        assert_eq!(parser.next(), Some(','));
+
+        let range = parser.alt([
+            &|p| {
+                let start = p.next_integer()?;
+                p.next_if_eq('|')?;
+                Some(start..p.next_integer()?)
+            },
+            &|p| {
+                let start = p.next_integer()?;
+                p.next_if_eq('-')?;
+                Some(start..p.next_integer()?)
+            },
+        ]);
+        assert_eq!(range, Some(12..34));
+
+        assert_eq!(parser.opt(|p| p.next_if_eq('-')), None);
+        let numbers = parser.many(|p| {
+            p.next_if_eq(' ')?;
+            p.next_integer()
+        });
+        assert_eq!(numbers, [5, 6, 7]);
+        assert_eq!(parser.next(), Some('x'));
+    }
+
+    #[test]
+    fn parser_take_while_and_until() {
+        let mut parser = parser_for!("abc123 def,ghi");
+
+        assert_eq!(parser.take_while(|c| c.is_ascii_alphabetic()), "abc");
+        assert_eq!(parser.take_while(|c| c.is_ascii_alphabetic()), "");
+        assert_eq!(parser.take_until(','), "123 def");
+        assert_eq!(parser.next(), Some(','));
+        assert_eq!(parser.take_until(','), "ghi");
+        assert_eq!(parser.next(), None);
+    }
+
+    #[test]
+    fn parser_take_while_into_buffer() {
+        let mut parser = parser_for!("42 7");
+        let mut buf = String::from(">");
+
+        assert!(parser
+            .take_while_into(&mut buf, |c| c.is_ascii_digit())
+            .is_ok());
+        assert_eq!(buf, ">42");
+        assert_eq!(parser.next(), Some(' '));
+    }
+
+    #[test]
+    fn parser_next_word() {
+        let mut parser = parser_for!("  x00 AND y01 -> z02\nbwr");
+
+        assert_eq!(parser.next_word().as_deref(), Some("x00"));
+        assert_eq!(parser.next_word().as_deref(), Some("AND"));
+        assert_eq!(parser.next_word().as_deref(), Some("y01"));
+        assert_eq!(parser.next_word(), None);
+        parser.skip(3);
+        assert_eq!(parser.next_word().as_deref(), Some("z02"));
+        assert_eq!(parser.next_word(), None);
+        assert_eq!(parser.take_newline(), Some(()));
+        assert_eq!(parser.next_word().as_deref(), Some("bwr"));
+    }
+
+    #[test]
+    fn parser_lines() {
+        let mut parser = parser_for!("7 6 4\r\n\n1 2 7\n9 7 6");
+
+        let lines: Vec<_> = parser.lines().map(|line| line.ok()).collect();
+        assert_eq!(
+            lines,
+            [
+                Some("7 6 4".into()),
+                Some("".into()),
+                Some("1 2 7".into()),
+                Some("9 7 6".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn parser_lines_after_partial_line() {
+        let mut parser = parser_for!("12: 3 4\n5\n");
+
+        assert_eq!(parser.next_integer(), Some(12));
+        let lines: Vec<_> = parser.lines().map(|line| line.ok()).collect();
+        assert_eq!(lines, [Some(": 3 4".into()), Some("5".into())]);
+    }
+
+    #[test]
+    fn parser_separated() {
+        let mut parser = parser_for!("75,47, 61\n1 2  3\n\n4");
+
+        assert_eq!(
+            parser.separated(',', |p| p.next_integer()).ok(),
+            Some(vec![75, 47, 61])
+        );
+        assert_eq!(
+            parser.separated(' ', |p| p.next_integer()).ok(),
+            Some(vec![1, 2, 3])
+        );
+        assert_eq!(
+            parser.separated(' ', |p| p.next_integer()).ok(),
+            Some(vec![])
+        );
+        assert_eq!(
+            parser.separated(' ', |p| p.next_integer()).ok(),
+            Some(vec![4])
+        );
+        assert_eq!(parser.eof(), Some(()));
+    }
+
+    #[test]
+    fn parser_separated_errors() {
+        let mut parser = parser_for!("1,2;3\n1,,2");
+
+        assert_eq!(
+            parser
+                .separated(',', |p| p.next_integer())
+                .map_err(|err| err.to_string()),
+            Err("expected ',' at line 1, column 4, found ';'".into())
+        );
+        parser.skip(3);
+        assert_eq!(
+            parser
+                .separated(',', |p| p.next_integer())
+                .map_err(|err| err.to_string()),
+            Err("expected value at line 2, column 3, found ','".into())
+        );
+    }
+
+    #[test]
+    fn parser_traces_recent_tokens() {
+        let mut parser = parser_for!("12|x\n");
+        parser.trace = Some(Trace::default());
+
+        let ordering = parser.seq(|p| {
+            let left = p.next_integer()?;
+            p.expect_char('|').ok()?;
+            Some((left, p.next_integer()?))
+        });
+        assert_eq!(ordering, None);
+        assert!(parser.expect_char('x').is_err());
+
+        let entries: Vec<_> = parser
+            .trace
+            .iter()
+            .flat_map(|trace| &trace.entries)
+            .map(|entry| format!("{} {} {}", entry.position, entry.method, entry.token))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                "line 1, column 1 integer 12",
+                "line 1, column 3 expect_char '|'",
+                "line 1, column 1 restore from line 1, column 4",
+            ]
+        );
+
+        for _ in 0..Trace::LEN {
+            parser.trace("skip", Position { line: 1, column: 1 }, "");
+        }
+        assert_eq!(
+            parser.trace.as_ref().map(|trace| trace.entries.len()),
+            Some(Trace::LEN)
+        );
+    }
+
+    #[test]
+    fn parser_key_value() {
+        let mut parser = parser_for!("Register A: 729\nButton A: X+94, Y+34\nno separator\n");
+
+        assert_eq!(
+            parser.key_value_as(':').ok(),
+            Some(Some(("Register A".to_string(), 729)))
+        );
+        assert_eq!(
+            parser.key_value(':'),
+            Some(("Button A".to_string(), "X+94, Y+34".to_string()))
+        );
+        assert_eq!(parser.key_value(':'), None);
+        assert_eq!(parser.take_until('\n'), "no separator");
+
+        let mut parser = parser_for!("Register A: lots\n");
+        assert_eq!(
+            parser
+                .key_value_as::<i64>(':')
+                .map_err(|err| err.to_string()),
+            Err(
+                "could not parse value \"lots\" of \"Register A\" at line 1, column 1: \
+                 invalid digit found in string"
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn parser_sections() {
+        let mut parser = parser_for!("\n1|2\n3|4\n\n  \n5,6\n7\n\n\nlast");
+
+        let sections: Vec<String> = parser
+            .sections()
+            .collect::<anyhow::Result<_>>()
+            .expect("sections to read");
+        assert_eq!(sections, ["1|2\n3|4\n", "5,6\n7\n", "last\n"]);
+        assert_eq!(parser.sections().count(), 0);
+
+        let source = [Ok(b'1'), Ok(b'\n'), Err(anyhow::anyhow!("broken pipe"))].into_iter();
+        let mut parser = Parser::new(source);
+        let mut sections = parser.sections();
+        assert!(sections.next().is_some_and(|section| section.is_err()));
+        assert!(sections.next().is_none());
+    }
+
+    #[test]
+    fn parser_parses_vec2() {
+        let mut parser = parser_for!("0,4 3, -3\n6,-1 2x");
+
+        assert_eq!(parser.next_vec2(','), Some(Vec2(0, 4)));
+        assert_eq!(parser.next_vec2(','), Some(Vec2(3, -3)));
+        assert_eq!(parser.take_newline(), Some(()));
+        assert_eq!(parser.next_vec2_usize(','), None);
+        assert_eq!(parser.next_integer(), Some(6));
+        assert_eq!(parser.next_vec2(','), None);
+        assert_eq!(parser.next(), Some(','));
+        assert_eq!(parser.next_vec2(','), None);
+        assert_eq!(parser.next_integer(), Some(-1));
+    }
+
+    #[test]
+    fn parser_takes_fields() {
+        let mut parser = parser_for!("p=0,4 v=3,-3\npos=1,2");
+
+        assert_eq!(parser.take_field("v"), None);
+        assert_eq!(parser.take_field("p"), Some(Vec2(0, 4)));
+        assert_eq!(parser.take_field("v"), Some(Vec2(3, -3)));
+        assert_eq!(parser.take_newline(), Some(()));
+        assert_eq!(parser.take_field("p"), None);
+        assert_eq!(parser.take_field("pos"), Some(Vec2(1, 2)));
+    }
+
+    #[test]
+    fn parser_takes_exact_literals() {
+        let mut parser = parser_for!("Prize: X=8400\nPri");
+
+        assert_eq!(parser.take_exact("Prize: ").ok(), Some(()));
+        assert_eq!(
+            parser.take_exact("Y=").map_err(|err| err.to_string()),
+            Err("expected \"Y=\" at line 1, column 8, found \"X=\"".into())
+        );
+        assert_eq!(parser.take_exact("X=").ok(), Some(()));
+        assert_eq!(parser.next_integer(), Some(8400));
+        assert_eq!(parser.take_newline(), Some(()));
+        assert_eq!(
+            parser.take_exact("Prize: ").map_err(|err| err.to_string()),
+            Err("expected \"Prize: \" at line 2, column 1, found \"Pri\"".into())
+        );
+        parser.skip(3);
+        assert_eq!(
+            parser.take_exact("!").map_err(|err| err.to_string()),
+            Err("expected \"!\" at line 2, column 4, found end of input".into())
+        );
+    }
+
+    #[test]
+    fn parser_peeks_line() {
+        let mut parser = parser_for!("47|53\n75,47\nlast");
+
+        assert_eq!(parser.peek_n(2), ['4', '7']);
+        assert_eq!(parser.peek_line(), ['4', '7', '|', '5', '3']);
+        assert_eq!(parser.next_integer(), Some(47));
+        assert_eq!(parser.peek_line(), ['|', '5', '3']);
+        parser.skip_line();
+        assert!(!parser.peek_line().contains(&'|'));
+        assert_eq!(parser.take_until('\n'), "75,47");
+        assert_eq!(parser.peek_line(), []);
+        parser.skip(1);
+        assert_eq!(parser.peek_line(), ['l', 'a', 's', 't']);
+        assert_eq!(parser.take_until('\n'), "last");
+        assert_eq!(parser.peek_line(), []);
+    }
+
+    #[test]
+    fn parser_takes_rest() {
+        let mut parser = parser_for!("header: 1\nfree-form ✓\ntext");
+
+        parser.skip_line();
+        assert_eq!(parser.peek_n(4), ['f', 'r', 'e', 'e']);
+        assert_eq!(parser.rest().ok(), Some("free-form ✓\ntext".to_string()));
+        assert_eq!(parser.rest().ok(), Some(String::new()));
+
+        let source = [b'a', b'b', 0xff, b'\n']
+            .into_iter()
+            .map(anyhow::Result::Ok);
+        let mut parser = Parser::new(source);
+        assert_eq!(parser.next(), Some('a'));
+        assert_eq!(parser.peek(), Some('b'));
+        assert_eq!(parser.rest_bytes().ok(), Some(vec![b'b', 0xff, b'\n']));
+    }
+
+    #[test]
+    fn parser_int_table() {
+        let mut parser = parser_for!("1 2  3\n\n-4 5\n6\n");
+
+        assert_eq!(
+            parser.int_table().ok(),
+            Some(vec![vec![1, 2, 3], vec![-4, 5], vec![6]])
+        );
+
+        let mut parser = parser_for!("1 2\n3 x\n");
+        assert_eq!(
+            parser.int_table().map_err(|err| format!("{err:#}")),
+            Err("line can only contain integers and whitespace: \
+                 expected value at line 2, column 3, found 'x'"
+                .into())
+        );
+    }
+
+    #[test]
+    fn parser_expect_errors() {
+        let mut parser = parser_for!("12|x\n3 4");
+
+        assert_eq!(parser.expect_integer().ok(), Some(12));
+        assert_eq!(parser.expect_char('|').ok(), Some(()));
+        assert_eq!(
+            parser.expect_integer().map_err(|err| err.to_string()),
+            Err("expected integer at line 1, column 4, found 'x'".into())
+        );
+        assert_eq!(
+            parser.expect_newline().map_err(|err| err.to_string()),
+            Err("expected newline at line 1, column 4, found 'x'".into())
+        );
+        parser.skip(2);
+        assert_eq!(parser.expect_integer().ok(), Some(3));
+        assert_eq!(
+            parser.expect_char(',').map_err(|err| err.to_string()),
+            Err("expected ',' at line 2, column 2, found ' '".into())
+        );
+        assert_eq!(parser.expect_integer().ok(), Some(4));
+        assert_eq!(
+            parser.expect_char(',').map_err(|err| err.to_string()),
+            Err("expected ',' at line 2, column 4, found end of input".into())
+        );
+        assert_eq!(parser.expect_newline().ok(), Some(()));
+    }
+
+    #[test]
+    fn parser_decodes_utf8() {
+        let mut parser = parser_for!("é ✓🎄!");
+
+        assert_eq!(parser.next(), Some('é'));
+        assert_eq!(parser.next(), Some(' '));
+        assert_eq!(parser.next(), Some('✓'));
+        assert_eq!(parser.next(), Some('🎄'));
+        assert_eq!(parser.next(), Some('!'));
+        assert_eq!(parser.next(), None);
+        assert!(parser.check().is_ok());
+    }
+
+    #[test]
+    fn parser_rejects_invalid_utf8() {
+        for bytes in [
+            &[b'a', 0xff][..],
+            &[b'a', 0xe2, 0x9c][..],
+            &[b'a', 0xc3, b'b'][..],
+            &[b'a', 0xc0, 0xaf][..],
+            &[b'a', 0xed, 0xa0, 0x80][..],
+        ] {
+            let mut parser = Parser::new(bytes.iter().map(|b| anyhow::Result::Ok(*b)));
+
+            assert_eq!(parser.next(), Some('a'));
+            assert_eq!(parser.next(), None);
+            assert!(parser.check().is_err(), "{bytes:?} should be invalid");
+        }
+    }
+
+    #[test]
+    fn parser_parses_hex_integers() {
+        let mut parser = parser_for!("ff 0x1A #70c710 -0Xff 0xg");
+
+        assert_eq!(parser.next_hex(), Some(255));
+        assert_eq!(parser.next_hex(), Some(26));
+        assert_eq!(parser.next_hex(), Some(0x70c710));
+        assert_eq!(parser.next_hex(), Some(-255));
+        assert_eq!(parser.next_hex(), Some(0));
+        assert_eq!(parser.next_hex(), None);
+        assert_eq!(parser.next(), Some('x'));
+    }
+
+    #[test]
+    fn parser_parses_binary_and_octal_integers() {
+        let mut parser = parser_for!("101 0b11 0o17 2");
+
+        assert_eq!(parser.next_binary(), Some(5));
+        assert_eq!(parser.next_binary(), Some(3));
+        assert_eq!(parser.next_integer_radix(8), Some(15));
+        assert_eq!(parser.next_binary(), None);
+        assert_eq!(parser.next(), Some('2'));
+    }
+
+    #[test]
+    fn parser_skip_line() {
+        let mut parser = parser_for!("# header\n\n12\n# trailer");
+
+        parser.skip_line();
+        assert_eq!(parser.next(), Some('\n'));
+        assert_eq!(parser.next_integer(), Some(12));
+        parser.skip_line();
+        parser.skip_line();
+        assert_eq!(parser.next(), None);
+    }
+
+    #[test]
+    fn parser_grid_stops_at_blank_line() {
+        let mut parser = parser_for!("#..\n.@#\n\n<>^\n");
+
+        let grid = parser.grid().expect("grid to parse");
+        assert_eq!(grid.max(), Vec2(2, 1));
+        assert_eq!(grid.get(Vec2(1, 1)), Some(&'@'));
+        assert_eq!(parser.take_until('\n'), "<>^");
+    }
+
+    #[test]
+    fn parser_grid_errors() {
+        assert!(parser_for!("#..\n.@\n").grid().is_err());
+        assert!(parser_for!("\n#..").grid().is_err());
+    }
+
     #[test]
     fn parser_take_newline() {
         let mut parser = parser_for!("1\n\n2");
@@ -401,14 +2197,36 @@ mod test {
     fn parser_peek_n() {
         let mut parser = parser_for!("hello world");
 
-        assert_eq!(parser.peek_n(3), "hel");
-        assert_eq!(parser.peek_n(4), "hell");
-        assert_eq!(parser.peek_n(2), "he");
+        assert_eq!(parser.peek_n(3), ['h', 'e', 'l']);
+        assert_eq!(parser.peek_n(4), ['h', 'e', 'l', 'l']);
+        assert_eq!(parser.peek_n(2), ['h', 'e']);
 
         parser.skip(8);
 
-        assert_eq!(parser.peek_n(3), "rld");
-        assert_eq!(parser.peek_n(5), "rld");
+        assert_eq!(parser.peek_n(3), ['r', 'l', 'd']);
+        assert_eq!(parser.peek_n(5), ['r', 'l', 'd']);
+    }
+
+    #[test]
+    fn parser_take_pattern() {
+        let mut parser = parser_for!("mul(2,4)mul(3,x)set a=-7 100%");
+
+        assert_eq!(
+            parser.take_pattern("mul(%d,%d)"),
+            Some(vec![Capture::Integer(2), Capture::Integer(4)])
+        );
+        assert_eq!(parser.take_pattern("mul(%d,%d)"), None);
+        assert_eq!(parser.position().column, 9);
+        parser.skip(8);
+        assert_eq!(
+            parser.take_pattern("set %w=%d %d%%"),
+            Some(vec![
+                Capture::Word("a".into()),
+                Capture::Integer(-7),
+                Capture::Integer(100)
+            ])
+        );
+        assert_eq!(parser.next(), None);
     }
 
     #[test]
@@ -436,6 +2254,25 @@ mod test {
         assert_eq!(parser.take_matching(numbers!()), None);
     }
 
+    #[test]
+    fn parser_take_keyword_prefers_first_listed() {
+        let keywords = Keywords::new([("do", 1), ("don't", 2), ("d", 3), ("mul(", 4)]);
+        let mut parser = parser_for!("don't do dmul(");
+
+        assert_eq!(parser.take_keyword(&keywords), Some(&1));
+        assert_eq!(parser.take_keyword(&keywords), None);
+        parser.skip(4);
+        assert_eq!(parser.take_keyword(&keywords), Some(&1));
+        parser.skip(1);
+        assert_eq!(parser.take_keyword(&keywords), Some(&3));
+        assert_eq!(parser.take_keyword(&keywords), Some(&4));
+        assert_eq!(parser.next(), None);
+
+        let keywords = Keywords::new([("don't", 2), ("do", 1)]);
+        let mut parser = parser_for!("don't");
+        assert_eq!(parser.take_keyword(&keywords), Some(&2));
+    }
+
     #[test]
     fn parser_take_matching_and() {
         let mut parser = parser_for!("onetowthreefonefive");
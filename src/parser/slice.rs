@@ -0,0 +1,434 @@
+use std::{num::ParseIntError, str::FromStr};
+
+use anyhow::{anyhow, Context};
+
+use super::{Keywords, Position};
+use crate::{error::Kind, grid::Grid2D};
+
+/// A parser over input that is already in memory, e.g. a whole puzzle input read into a `String`.
+/// Unlike [Parser](super::Parser) there are no read errors to carry around and nothing to buffer,
+/// so it is a cursor into the input, and tokens are returned as slices of it rather than copied.
+///
+/// The method names and behaviour follow [Parser](super::Parser) wherever they overlap.
+///
+/// The CLI hands every day a stream rather than a [SliceParser], so a day opts in by reading its
+/// input into a `String` first.
+pub struct SliceParser<'a> {
+    src: &'a str,
+    /// Byte offset of the next character to be consumed.
+    pos: usize,
+}
+
+impl<'a> From<&'a str> for SliceParser<'a> {
+    fn from(src: &'a str) -> Self {
+        Self::new(src)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for SliceParser<'a> {
+    type Error = anyhow::Error;
+
+    fn try_from(bytes: &'a [u8]) -> anyhow::Result<Self> {
+        std::str::from_utf8(bytes).map(Self::new).map_err(|err| {
+            Kind::Parse.error(format!("invalid UTF-8 at byte {}", err.valid_up_to()))
+        })
+    }
+}
+
+impl<'a> SliceParser<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    /// Everything that has not been consumed yet.
+    pub fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    /// The position of the next character to be consumed. This is worked out by counting lines
+    /// from the start of the input, so is meant for error messages rather than every token.
+    pub fn position(&self) -> Position {
+        let consumed = &self.src[..self.pos];
+        let line_start = consumed.rfind('\n').map_or(0, |i| i + 1);
+        Position {
+            line: consumed.matches('\n').count() + 1,
+            column: consumed[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// See [Parser::eof](super::Parser::eof).
+    pub fn eof(&mut self) -> Option<()> {
+        self.skip_if_eq(' ');
+        self.rest().is_empty().then_some(())
+    }
+
+    /// See [Parser::take_newline](super::Parser::take_newline).
+    pub fn take_newline(&mut self) -> Option<()> {
+        self.skip_if_eq(' ');
+        self.next_if_eq('\n').and(Some(()))
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<char> {
+        let next = self.peek()?;
+        self.pos += next.len_utf8();
+        Some(next)
+    }
+
+    pub fn next_if<F: Fn(char) -> bool>(&mut self, f: F) -> Option<char> {
+        self.peek().filter(|c| f(*c)).and_then(|_| self.next())
+    }
+
+    pub fn next_if_eq(&mut self, c: char) -> Option<char> {
+        self.next_if(|next| next == c)
+    }
+
+    /// Consume `n` characters, or up to the end of the input.
+    pub fn skip(&mut self, n: usize) {
+        for _ in 0..n {
+            if self.next().is_none() {
+                break;
+            }
+        }
+    }
+
+    pub fn skip_if_eq(&mut self, c: char) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start_matches(c).len();
+    }
+
+    pub fn skip_if<F: Fn(char) -> bool>(&mut self, f: F) {
+        self.take_while(f);
+    }
+
+    /// See [Parser::skip_line](super::Parser::skip_line).
+    pub fn skip_line(&mut self) {
+        self.take_until('\n');
+        self.next_if_eq('\n');
+    }
+
+    /// Consumes characters while they match `f`, returning them as a slice of the input.
+    pub fn take_while<F: Fn(char) -> bool>(&mut self, f: F) -> &'a str {
+        let rest = self.rest();
+        let len = rest.len() - rest.trim_start_matches(f).len();
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// Consumes characters up to (but not including) `delimiter`, or the end of the input.
+    pub fn take_until(&mut self, delimiter: char) -> &'a str {
+        self.take_while(|c| c != delimiter)
+    }
+
+    /// See [Parser::next_word](super::Parser::next_word).
+    pub fn next_word(&mut self) -> Option<&'a str> {
+        self.skip_if_eq(' ');
+        Some(self.take_while(char::is_alphanumeric)).filter(|word| !word.is_empty())
+    }
+
+    /// See [Parser::next_integer](super::Parser::next_integer).
+    pub fn next_integer(&mut self) -> Option<i64> {
+        self.skip_if_eq(' ');
+        self.integer()
+    }
+
+    /// See [Parser::integer](super::Parser::integer).
+    pub fn integer(&mut self) -> Option<i64> {
+        self.try_integer().ok().flatten()
+    }
+
+    /// See [Parser::try_next_integer](super::Parser::try_next_integer).
+    pub fn try_next_integer<T: FromStr<Err = ParseIntError>>(
+        &mut self,
+    ) -> anyhow::Result<Option<T>> {
+        self.skip_if_eq(' ');
+        self.try_integer()
+    }
+
+    /// See [Parser::try_integer](super::Parser::try_integer).
+    pub fn try_integer<T: FromStr<Err = ParseIntError>>(&mut self) -> anyhow::Result<Option<T>> {
+        self.parse_integer(true)
+    }
+
+    /// See [Parser::next_uint](super::Parser::next_uint).
+    pub fn next_uint(&mut self) -> Option<u64> {
+        self.skip_if_eq(' ');
+        self.parse_integer(false).ok().flatten()
+    }
+
+    fn parse_integer<T: FromStr<Err = ParseIntError>>(
+        &mut self,
+        signed: bool,
+    ) -> anyhow::Result<Option<T>> {
+        let rest = self.rest().as_bytes();
+        let sign = usize::from(signed && rest.first() == Some(&b'-'));
+        let digits = rest[sign..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if digits == 0 {
+            return Ok(None);
+        }
+
+        let token = &self.rest()[..sign + digits];
+        match token.parse() {
+            Ok(n) => {
+                self.pos += token.len();
+                Ok(Some(n))
+            }
+            Err(err) => Err(Kind::Parse.wrap(anyhow!(
+                "could not parse integer {token} at {} as {}: {err}",
+                self.position(),
+                std::any::type_name::<T>()
+            ))),
+        }
+    }
+
+    /// See [Parser::take_matching](super::Parser::take_matching).
+    pub fn take_matching<V: IntoIterator<Item = &'static str>>(
+        &mut self,
+        v: V,
+    ) -> Option<&'static str> {
+        self.take_keyword(&Keywords::new(v.into_iter().map(|s| (s, s))))
+            .copied()
+    }
+
+    /// See [Parser::take_keyword](super::Parser::take_keyword).
+    pub fn take_keyword<'k, T>(&mut self, keywords: &'k Keywords<T>) -> Option<&'k T> {
+        let mut node = 0;
+        let mut matched = keywords.nodes[node].value.map(|index| (index, 0));
+
+        for (i, c) in self.rest().char_indices() {
+            let Some(child) = keywords.nodes[node].child(c) else {
+                break;
+            };
+            node = child;
+            if let Some(index) = keywords.nodes[node].value {
+                if matched.is_none_or(|(best, _)| index < best) {
+                    matched = Some((index, i + c.len_utf8()));
+                }
+            }
+        }
+
+        let (index, len) = matched?;
+        self.pos += len;
+        Some(&keywords.values[index])
+    }
+
+    /// See [Parser::separated](super::Parser::separated).
+    pub fn separated<T, F: FnMut(&mut Self) -> Option<T>>(
+        &mut self,
+        delimiter: char,
+        mut f: F,
+    ) -> anyhow::Result<Vec<T>> {
+        let mut values = vec![];
+
+        while self.take_newline().or_else(|| self.eof()).is_none() {
+            if !values.is_empty() && delimiter != ' ' && self.next_if_eq(delimiter).is_none() {
+                return Err(self.unexpected(format!("{delimiter:?}")));
+            }
+
+            self.skip_if_eq(' ');
+            match f(self) {
+                Some(value) => values.push(value),
+                None => return Err(self.unexpected("value")),
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// See [Parser::int_table](super::Parser::int_table).
+    pub fn int_table(&mut self) -> anyhow::Result<Vec<Vec<i64>>> {
+        let mut rows = vec![];
+        while self.eof().is_none() {
+            let row = self
+                .separated(' ', |p| p.next_integer())
+                .context("line can only contain integers and whitespace")?;
+            if !row.is_empty() {
+                rows.push(row);
+            }
+        }
+
+        Ok(rows)
+    }
+
+    /// See [Parser::expect_integer](super::Parser::expect_integer).
+    pub fn expect_integer(&mut self) -> anyhow::Result<i64> {
+        match self.try_next_integer()? {
+            Some(n) => Ok(n),
+            None => Err(self.unexpected("integer")),
+        }
+    }
+
+    /// See [Parser::expect_char](super::Parser::expect_char).
+    pub fn expect_char(&mut self, c: char) -> anyhow::Result<()> {
+        match self.next_if_eq(c) {
+            Some(_) => Ok(()),
+            None => Err(self.unexpected(format!("{c:?}"))),
+        }
+    }
+
+    /// See [Parser::expect_newline](super::Parser::expect_newline).
+    pub fn expect_newline(&mut self) -> anyhow::Result<()> {
+        match self.take_newline().or_else(|| self.eof()) {
+            Some(()) => Ok(()),
+            None => Err(self.unexpected("newline")),
+        }
+    }
+
+    fn unexpected(&self, expected: impl std::fmt::Display) -> anyhow::Error {
+        let position = self.position();
+        Kind::Parse.wrap(match self.peek() {
+            Some(c) => anyhow!("expected {expected} at {position}, found {c:?}"),
+            None => anyhow!("expected {expected} at {position}, found end of input"),
+        })
+    }
+
+    /// See [Parser::lines](super::Parser::lines). The lines are slices of the input.
+    pub fn lines(&mut self) -> impl Iterator<Item = &'a str> + '_ {
+        std::iter::from_fn(move || {
+            if self.rest().is_empty() {
+                return None;
+            }
+
+            let line = self.take_until('\n');
+            self.next_if_eq('\n');
+            Some(line.strip_suffix('\r').unwrap_or(line))
+        })
+    }
+
+    /// See [Parser::grid](super::Parser::grid).
+    pub fn grid(&mut self) -> anyhow::Result<Grid2D<char>> {
+        let start = self.position();
+        let begin = self.pos;
+        let mut width = None;
+        let mut end = self.pos;
+
+        loop {
+            let row = self.take_until('\n');
+            if row.is_empty() {
+                self.next_if_eq('\n');
+                break;
+            }
+            end = self.pos;
+
+            let row_width = row.chars().count();
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    return Err(Kind::Parse.wrap(anyhow!(
+                        "grid rows must all be {width} wide, but line {} is {row_width}",
+                        self.position().line
+                    )))
+                }
+                Some(_) => {}
+            }
+            self.next_if_eq('\n');
+        }
+
+        if width.is_none() {
+            return Err(Kind::Parse.error(format!("expected a grid at {start}")));
+        }
+
+        Ok(Grid2D::from(self.src[begin..end].chars()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SliceParser;
+    use crate::{
+        grid::Vec2,
+        parser::{Keywords, Position},
+    };
+
+    #[test]
+    fn slice_parser_returns_slices_of_input() {
+        let input = String::from("  abc123 def,ghi\nline two\r\nlast");
+        let mut parser = SliceParser::new(&input);
+
+        assert_eq!(parser.next_word(), Some("abc123"));
+        assert_eq!(parser.take_until(','), " def");
+        assert_eq!(parser.next(), Some(','));
+        let lines: Vec<&str> = parser.lines().collect();
+        assert_eq!(lines, ["ghi", "line two", "last"]);
+        assert_eq!(parser.eof(), Some(()));
+    }
+
+    #[test]
+    fn slice_parser_parses_integers() {
+        let mut parser = SliceParser::new("12 -3 2-4 - 99999999999");
+
+        assert_eq!(parser.next_integer(), Some(12));
+        assert_eq!(parser.next_integer(), Some(-3));
+        assert_eq!(parser.next_uint(), Some(2));
+        assert_eq!(parser.next_uint(), None);
+        assert_eq!(parser.next_integer(), Some(-4));
+        assert_eq!(parser.next_integer(), None);
+        assert_eq!(parser.next(), Some('-'));
+        assert_eq!(
+            parser
+                .try_next_integer::<i32>()
+                .map_err(|err| err.to_string()),
+            Err(
+                "could not parse integer 99999999999 at line 1, column 13 as i32: \
+                 number too large to fit in target type"
+                    .into()
+            )
+        );
+        assert_eq!(parser.next_integer(), Some(99999999999));
+    }
+
+    #[test]
+    fn slice_parser_matches_keywords() {
+        let keywords = Keywords::new([("don't()", 1), ("do()", 2), ("d", 3)]);
+        let mut parser = SliceParser::new("don't()do()dx");
+
+        assert_eq!(parser.take_keyword(&keywords), Some(&1));
+        assert_eq!(parser.take_matching(["mul(", "do()"]), Some("do()"));
+        assert_eq!(parser.take_keyword(&keywords), Some(&3));
+        assert_eq!(parser.take_keyword(&keywords), None);
+        assert_eq!(parser.rest(), "x");
+    }
+
+    #[test]
+    fn slice_parser_errors() {
+        let mut parser = SliceParser::new("1,2;3\né 4 x");
+
+        assert_eq!(
+            parser
+                .separated(',', |p| p.next_integer())
+                .map_err(|err| err.to_string()),
+            Err("expected ',' at line 1, column 4, found ';'".into())
+        );
+        parser.skip_line();
+        assert_eq!(parser.next(), Some('é'));
+        assert_eq!(parser.expect_integer().ok(), Some(4));
+        assert_eq!(parser.position(), Position { line: 2, column: 4 });
+        assert_eq!(
+            parser.expect_newline().map_err(|err| err.to_string()),
+            Err("expected newline at line 2, column 5, found 'x'".into())
+        );
+    }
+
+    #[test]
+    fn slice_parser_grid() {
+        let mut parser = SliceParser::new("ab\ncd\n\nmoves");
+
+        let grid = parser.grid().expect("grid to parse");
+        assert_eq!(grid.max(), Vec2(1, 1));
+        assert_eq!(grid.get(Vec2(0, 1)), Some(&'c'));
+        assert_eq!(parser.rest(), "moves");
+
+        let mut parser = SliceParser::new("ab\nc\n");
+        assert_eq!(
+            parser.grid().map(|_| ()).map_err(|err| err.to_string()),
+            Err("grid rows must all be 2 wide, but line 2 is 1".into())
+        );
+    }
+}
@@ -0,0 +1,426 @@
+use std::{
+    fs,
+    io::{BufRead, Cursor},
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+
+use crate::{day::Day, error::Kind};
+
+/// The input type days are instantiated with when driven from the CLI.
+pub type Input = Box<dyn BufRead>;
+
+/// A day instantiated for the CLI's input type.
+pub type Solution = Day<Input>;
+
+/// The outcome of running a single part of a puzzle.
+pub struct PartRun {
+    pub answer: anyhow::Result<String>,
+    pub duration: Duration,
+}
+
+/// The outcome of running both parts of a single day.
+pub struct DayRun {
+    pub day: u8,
+    pub part_1: PartRun,
+    pub part_2: Option<PartRun>,
+}
+
+impl DayRun {
+    /// Iterates over the parts that were run, alongside their part number.
+    pub fn parts(&self) -> impl Iterator<Item = (u8, &PartRun)> {
+        std::iter::once((1, &self.part_1)).chain(self.part_2.as_ref().map(|run| (2, run)))
+    }
+}
+
+/// Returns the conventional location of a day's puzzle input within `dir`, e.g.
+/// `.input/day6.txt`.
+pub fn input_path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{day}.txt"))
+}
+
+/// Runs both parts of every day in `days` against the inputs discovered in `dir`. Days whose
+/// input cannot be read are still reported, with each part holding the read error.
+///
+/// With `parallel`, every day and part runs on its own thread. Runs are still returned in the
+/// order of `days`, but each part's duration then includes contention with the others.
+pub fn run_all(days: &[(u8, Solution)], dir: &Path, parallel: bool) -> Vec<DayRun> {
+    if !parallel {
+        return days
+            .iter()
+            .map(|(n, day)| run_day(*n, day, dir, false))
+            .collect();
+    }
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = days
+            .iter()
+            .map(|(n, day)| scope.spawn(move || run_day(*n, day, dir, true)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    })
+}
+
+fn run_day(n: u8, day: &Solution, dir: &Path, parallel: bool) -> DayRun {
+    let path = input_path(dir, n);
+    let bytes = match fs::read(&path).map(Arc::<[u8]>::from) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            let missing = || PartRun {
+                answer: Err(match err.kind() {
+                    std::io::ErrorKind::NotFound => Kind::InputNotFound
+                        .error(format!("could not read {}: {err}", path.display())),
+                    _ => anyhow!("could not read {}: {err}", path.display()),
+                }),
+                duration: Duration::ZERO,
+            };
+            return DayRun {
+                day: n,
+                part_1: missing(),
+                part_2: day.has_part_2().then(missing),
+            };
+        }
+    };
+
+    let part_1 = || run_part(|input| day.run_part_1(input), shared_input(&bytes));
+    let part_2 = || {
+        day.has_part_2()
+            .then(|| run_part(|input| day.run_part_2(input), shared_input(&bytes)))
+    };
+
+    let (part_1, part_2) = if parallel {
+        std::thread::scope(|scope| {
+            let part_2 = scope.spawn(part_2);
+            let part_1 = part_1();
+            let part_2 = part_2
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            (part_1, part_2)
+        })
+    } else {
+        (part_1(), part_2())
+    };
+
+    DayRun {
+        day: n,
+        part_1,
+        part_2,
+    }
+}
+
+/// Aggregate figures over a set of day runs, for the `stats` subcommand.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Totals {
+    /// How many parts solved successfully.
+    pub solved: usize,
+    /// How many parts failed, including those whose input could not be read.
+    pub failed: usize,
+    /// The combined duration of every part that solved.
+    pub duration: Duration,
+    /// The day, part number and duration of the slowest part that solved.
+    pub slowest: Option<(u8, u8, Duration)>,
+}
+
+impl Totals {
+    pub fn of(runs: &[DayRun]) -> Self {
+        let mut totals = Self {
+            solved: 0,
+            failed: 0,
+            duration: Duration::ZERO,
+            slowest: None,
+        };
+
+        for run in runs {
+            for (part, part_run) in run.parts() {
+                if part_run.answer.is_err() {
+                    totals.failed += 1;
+                    continue;
+                }
+
+                totals.solved += 1;
+                totals.duration += part_run.duration;
+                if totals
+                    .slowest
+                    .is_none_or(|(_, _, slowest)| part_run.duration > slowest)
+                {
+                    totals.slowest = Some((run.day, part, part_run.duration));
+                }
+            }
+        }
+
+        totals
+    }
+}
+
+/// Wraps an in-memory puzzle input so that it can be handed to several parts without copying.
+pub fn shared_input(bytes: &Arc<[u8]>) -> Input {
+    Box::new(Cursor::new(Arc::clone(bytes)))
+}
+
+/// Runs a part function against `input`, timing only the part function itself. For an in-memory
+/// input (see [shared_input]) that excludes reading the input, whereas a streamed input is read as
+/// the part function consumes it.
+pub fn run_part<F: FnOnce(Input) -> anyhow::Result<String>>(part_fn: F, input: Input) -> PartRun {
+    let start = Instant::now();
+    let answer = part_fn(input);
+    let duration = start.elapsed();
+
+    PartRun { answer, duration }
+}
+
+/// Runs `f`, calling `on_timeout` from a watchdog thread if `f` is still running after `timeout`.
+/// A running part cannot be interrupted, so `on_timeout` will usually end the process.
+pub fn watchdog<T>(
+    timeout: Duration,
+    on_timeout: impl FnOnce() + Send + 'static,
+    f: impl FnOnce() -> T,
+) -> T {
+    let (done, finished) = mpsc::channel::<()>();
+    let watchdog = std::thread::spawn(move || {
+        if let Err(mpsc::RecvTimeoutError::Timeout) = finished.recv_timeout(timeout) {
+            on_timeout();
+        }
+    });
+
+    let value = f();
+
+    // Dropping the sender wakes the watchdog if it is still waiting.
+    drop(done);
+    watchdog
+        .join()
+        .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+
+    value
+}
+
+/// Summary statistics over repeated runs of a single part.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Timings {
+    pub iterations: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl Timings {
+    /// Summarise a non-empty set of run durations.
+    pub fn from_durations(mut durations: Vec<Duration>) -> Option<Self> {
+        if durations.is_empty() {
+            return None;
+        }
+
+        durations.sort_unstable();
+        let n = durations.len();
+        let median = if n.is_multiple_of(2) {
+            (durations[n / 2 - 1] + durations[n / 2]) / 2
+        } else {
+            durations[n / 2]
+        };
+
+        Some(Self {
+            iterations: n,
+            min: durations[0],
+            median,
+            mean: durations.iter().sum::<Duration>() / n as u32,
+        })
+    }
+}
+
+/// Runs a part function `iterations` times (at least once) against a shared in-memory input,
+/// returning the first run along with timings over every run. Stops at the first error, returning
+/// the failed run without timings.
+pub fn repeat<F: Fn(Input) -> anyhow::Result<String>>(
+    part_fn: F,
+    bytes: &Arc<[u8]>,
+    iterations: usize,
+) -> (PartRun, Option<Timings>) {
+    let first = run_part(&part_fn, shared_input(bytes));
+    if first.answer.is_err() {
+        return (first, None);
+    }
+
+    let mut durations = vec![first.duration];
+    for _ in 1..iterations {
+        let run = run_part(&part_fn, shared_input(bytes));
+        if run.answer.is_err() {
+            return (run, None);
+        }
+        durations.push(run.duration);
+    }
+
+    (first, Timings::from_durations(durations))
+}
+
+/// Runs a part function `iterations` times against a shared in-memory input, stopping at the
+/// first error.
+pub fn bench<F: Fn(Input) -> anyhow::Result<String>>(
+    part_fn: F,
+    bytes: &Arc<[u8]>,
+    iterations: usize,
+) -> anyhow::Result<Timings> {
+    if iterations == 0 {
+        return Err(anyhow!("iterations must be at least 1"));
+    }
+
+    let (run, timings) = repeat(part_fn, bytes, iterations);
+    run.answer?;
+    timings.ok_or(anyhow!("iterations must be at least 1"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn input_path_uses_unpadded_day_number() {
+        assert_eq!(
+            input_path(Path::new(".input"), 6),
+            PathBuf::from(".input/day6.txt")
+        );
+    }
+
+    #[test]
+    fn timings_from_odd_number_of_durations() {
+        let timings =
+            Timings::from_durations([5, 1, 3].into_iter().map(Duration::from_millis).collect());
+
+        assert_eq!(
+            timings,
+            Some(Timings {
+                iterations: 3,
+                min: Duration::from_millis(1),
+                median: Duration::from_millis(3),
+                mean: Duration::from_millis(3),
+            })
+        );
+    }
+
+    #[test]
+    fn timings_from_even_number_of_durations() {
+        let timings = Timings::from_durations(
+            [4, 1, 2, 9]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect(),
+        )
+        .expect("timings for non-empty durations");
+
+        assert_eq!(timings.median, Duration::from_millis(3));
+        assert_eq!(timings.mean, Duration::from_millis(4));
+    }
+
+    #[test]
+    fn timings_from_no_durations() {
+        assert_eq!(Timings::from_durations(vec![]), None);
+    }
+
+    #[test]
+    fn run_all_in_parallel_keeps_day_order() {
+        let dir = std::env::temp_dir().join(format!("aoc2024-run-all-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("temp dir to be created");
+        fs::write(
+            input_path(&dir, 1),
+            "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n",
+        )
+        .expect("input to be written");
+
+        let days = [
+            (1, crate::day_01::solution()),
+            (2, crate::day_02::solution()),
+        ];
+        let runs = run_all(&days, &dir, true);
+        fs::remove_dir_all(&dir).expect("temp dir to be removed");
+
+        assert_eq!(runs.iter().map(|run| run.day).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(runs[0].part_1.answer.as_deref().ok(), Some("11"));
+        assert_eq!(
+            runs[0]
+                .part_2
+                .as_ref()
+                .and_then(|run| run.answer.as_deref().ok()),
+            Some("31")
+        );
+        assert!(runs[1].part_1.answer.is_err());
+    }
+
+    #[test]
+    fn repeat_keeps_first_answer_and_times_every_run() {
+        let bytes = Arc::<[u8]>::from(&b"3   4\n4   3\n"[..]);
+        let day = crate::day_01::solution::<Input>();
+
+        let (run, timings) = repeat(|input| day.run_part_1(input), &bytes, 4);
+
+        assert_eq!(run.answer.as_deref().ok(), Some("0"));
+        assert_eq!(timings.map(|t| t.iterations), Some(4));
+    }
+
+    #[test]
+    fn totals_skip_failed_parts() {
+        let run = |answer: anyhow::Result<&str>, ms| PartRun {
+            answer: answer.map(str::to_string),
+            duration: Duration::from_millis(ms),
+        };
+        let runs = [
+            DayRun {
+                day: 1,
+                part_1: run(Ok("11"), 2),
+                part_2: Some(run(Ok("31"), 5)),
+            },
+            DayRun {
+                day: 2,
+                part_1: run(Err(anyhow!("bad input")), 90),
+                part_2: None,
+            },
+            DayRun {
+                day: 3,
+                part_1: run(Ok("161"), 3),
+                part_2: Some(run(Ok("48"), 4)),
+            },
+        ];
+
+        assert_eq!(
+            Totals::of(&runs),
+            Totals {
+                solved: 4,
+                failed: 1,
+                duration: Duration::from_millis(14),
+                slowest: Some((1, 2, Duration::from_millis(5))),
+            }
+        );
+    }
+
+    #[test]
+    fn watchdog_only_fires_after_timeout() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let fired = Arc::new(AtomicBool::new(false));
+
+        let flag = Arc::clone(&fired);
+        let value = watchdog(
+            Duration::from_secs(10),
+            move || flag.store(true, Ordering::SeqCst),
+            || 7,
+        );
+        assert_eq!(value, 7);
+        assert!(!fired.load(Ordering::SeqCst));
+
+        let flag = Arc::clone(&fired);
+        watchdog(
+            Duration::from_millis(1),
+            move || flag.store(true, Ordering::SeqCst),
+            || std::thread::sleep(Duration::from_millis(50)),
+        );
+        assert!(fired.load(Ordering::SeqCst));
+    }
+}
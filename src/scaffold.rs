@@ -0,0 +1,227 @@
+use std::{fs, path::Path};
+
+use anyhow::anyhow;
+
+const TEMPLATE: &str = r#"use std::io::{BufRead, Read};
+
+use anyhow::anyhow;
+
+use crate::{
+    day::Day,
+    error::Kind,
+    parser::{BytesParser, Parser},
+};
+
+struct Lines<R: Read> {
+    parser: BytesParser<R>,
+}
+
+impl<R: Read> From<R> for Lines<R> {
+    fn from(value: R) -> Self {
+        Self {
+            parser: Parser::from(value),
+        }
+    }
+}
+
+impl<R: Read> Iterator for Lines<R> {
+    type Item = anyhow::Result<Vec<i64>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.parser.eof().is_some() {
+            return None;
+        }
+
+        Some(Err(anyhow!("parsing not implemented")))
+    }
+}
+
+fn part_1<I: BufRead>(input: I) -> anyhow::Result<String> {
+    for line in Lines::from(input) {
+        line?;
+    }
+
+    Err(Kind::Unimplemented.error("part 1 not implemented"))
+}
+
+fn part_2<I: BufRead>(input: I) -> anyhow::Result<String> {
+    for line in Lines::from(input) {
+        line?;
+    }
+
+    Err(Kind::Unimplemented.error("part 2 not implemented"))
+}
+
+const EXAMPLE: &str = "";
+
+pub fn solution<I: BufRead>() -> Day<I> {
+    Day::part_1(part_1)
+        .example_1(EXAMPLE, "")
+        .part_2(part_2)
+        .example_2(EXAMPLE, "")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_solution;
+
+    use super::*;
+
+    test_solution! {
+        #[ignore = "part 1 not implemented"]
+        part_1 part_one_default_case
+        EXAMPLE,
+        ""
+    }
+
+    test_solution! {
+        #[ignore = "part 2 not implemented"]
+        part_2 part_two_default_case
+        EXAMPLE,
+        ""
+    }
+}
+"#;
+
+/// Generates `src/day_NN.rs` for `day` under the repository `root`, and registers it in
+/// `src/lib.rs` and the `days!` list in `src/main.rs`. Returns the path of the new module.
+pub fn scaffold(root: &Path, day: u8) -> anyhow::Result<String> {
+    if !(1..=25).contains(&day) {
+        return Err(anyhow!("day must be between 1 and 25"));
+    }
+
+    let src = root.join("src");
+    let lib_path = src.join("lib.rs");
+    let main_path = src.join("main.rs");
+    if !lib_path.exists() || !main_path.exists() {
+        return Err(anyhow!("run scaffold from the repository root"));
+    }
+
+    let module = format!("src/day_{day:02}.rs");
+    let module_path = root.join(&module);
+    if module_path.exists() {
+        return Err(anyhow!("{module} already exists"));
+    }
+
+    let lib = add_module(&fs::read_to_string(&lib_path)?, day)?;
+    let main = add_day(&fs::read_to_string(&main_path)?, day)?;
+
+    fs::write(&module_path, TEMPLATE)?;
+    fs::write(&lib_path, lib)?;
+    fs::write(&main_path, main)?;
+
+    Ok(module)
+}
+
+/// Inserts `pub mod day_NN;` into `lib.rs`, keeping the day modules in order.
+fn add_module(lib: &str, day: u8) -> anyhow::Result<String> {
+    let decl = format!("pub mod day_{day:02};");
+    let mut lines: Vec<&str> = lib.lines().collect();
+
+    if lines.contains(&decl.as_str()) {
+        return Err(anyhow!("day {day} is already declared in lib.rs"));
+    }
+
+    let is_day = |line: &&str| line.starts_with("pub mod day_");
+    let idx = lines
+        .iter()
+        .position(|line| is_day(line) && *line > decl.as_str())
+        .or_else(|| lines.iter().rposition(is_day).map(|i| i + 1))
+        .ok_or(anyhow!("could not find day modules in lib.rs"))?;
+
+    lines.insert(idx, &decl);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds `N => day_NN::solution()` to the `days!` list in `main.rs`, keeping days in order.
+fn add_day(main: &str, day: u8) -> anyhow::Result<String> {
+    let start = main
+        .find("gen::days! {\n")
+        .map(|i| i + "gen::days! {\n".len())
+        .ok_or(anyhow!("could not find the days! list in main.rs"))?;
+    let end = start
+        + main[start..].find("\n}").ok_or(anyhow!(
+            "could not find the end of the days! list in main.rs"
+        ))?;
+
+    let number = |entry: &str| -> Option<u8> { entry.split_once(" => ")?.0.parse().ok() };
+
+    let mut entries: Vec<String> = main[start..end]
+        .lines()
+        .map(|line| line.trim().trim_end_matches(',').to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    if entries.iter().any(|entry| number(entry) == Some(day)) {
+        return Err(anyhow!("day {day} is already in the days! list"));
+    }
+
+    entries.push(format!("{day} => day_{day:02}::solution()"));
+    entries.sort_by_key(|entry| number(entry));
+
+    let list = entries
+        .iter()
+        .map(|entry| format!("    {entry}"))
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    Ok(format!("{}{list}{}", &main[..start], &main[end..]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn add_module_keeps_days_in_order() {
+        let lib = "pub mod day;
+pub mod day_01;
+pub mod day_11;
+mod grid;
+";
+
+        assert_eq!(
+            add_module(lib, 5).expect("module to be added"),
+            "pub mod day;
+pub mod day_01;
+pub mod day_05;
+pub mod day_11;
+mod grid;
+"
+        );
+        assert_eq!(
+            add_module(lib, 12).expect("module to be added"),
+            "pub mod day;
+pub mod day_01;
+pub mod day_11;
+pub mod day_12;
+mod grid;
+"
+        );
+        assert!(add_module(lib, 1).is_err());
+    }
+
+    #[test]
+    fn add_day_keeps_days_in_order() {
+        let main = "gen::days! {
+    1 => day_01::solution(),
+    25 => day_25::solution()
+}
+
+fn main() {}
+";
+
+        assert_eq!(
+            add_day(main, 12).expect("day to be added"),
+            "gen::days! {
+    1 => day_01::solution(),
+    12 => day_12::solution(),
+    25 => day_25::solution()
+}
+
+fn main() {}
+"
+        );
+        assert!(add_day(main, 25).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use std::{cmp::Reverse, collections::BinaryHeap, hash::Hash};
+
+use gxhash::{HashMap, HashMapExt};
+
+/// The cheapest way found to a goal by [dijkstra] or [astar].
+#[derive(Debug, PartialEq, Eq)]
+pub struct Found<S> {
+    pub cost: u64,
+    /// Every path of that cost, each running from the start state to a goal state inclusive.
+    pub paths: Vec<Vec<S>>,
+}
+
+impl<S: Eq + Hash> Found<S> {
+    #[allow(unused)]
+    /// The distinct states on any of the best paths, e.g. the tiles worth sitting on.
+    pub fn states(&self) -> gxhash::HashSet<&S> {
+        self.paths.iter().flatten().collect()
+    }
+}
+
+#[allow(unused)]
+/// Finds the cheapest paths from `start` to any state for which `is_goal` holds, where
+/// `successors` gives the states reachable from a state along with the cost of each step.
+///
+/// States can be anything hashable, such as a position paired with a facing. Returns `None` if no
+/// goal can be reached.
+pub fn dijkstra<S, F, I, G>(start: S, successors: F, is_goal: G) -> Option<Found<S>>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+    G: FnMut(&S) -> bool,
+{
+    astar(start, successors, is_goal, |_| 0)
+}
+
+#[allow(unused)]
+/// Like [dijkstra], but guided towards the goal by `heuristic`, an estimate of the remaining cost
+/// from a state.
+///
+/// The heuristic must never overestimate the remaining cost, or the paths found may not be the
+/// cheapest.
+pub fn astar<S, F, I, G, H>(
+    start: S,
+    mut successors: F,
+    mut is_goal: G,
+    mut heuristic: H,
+) -> Option<Found<S>>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+    G: FnMut(&S) -> bool,
+    H: FnMut(&S) -> u64,
+{
+    // States are numbered in the order they're discovered, so the queue and the path
+    // bookkeeping only need to deal in indices.
+    let mut states = vec![start.clone()];
+    let mut indices = HashMap::new();
+    indices.insert(start.clone(), 0);
+    let mut costs = vec![0];
+    let mut previous: Vec<Vec<usize>> = vec![vec![]];
+
+    let mut queue = BinaryHeap::new();
+    queue.push(Reverse((heuristic(&start), 0, 0)));
+
+    let mut best = None;
+    let mut goals = vec![];
+
+    while let Some(Reverse((estimate, cost, i))) = queue.pop() {
+        if cost > costs[i] {
+            continue;
+        }
+        if best.is_some_and(|best| estimate > best) {
+            break;
+        }
+
+        let state = states[i].clone();
+        if is_goal(&state) {
+            best = Some(cost);
+            goals.push(i);
+            continue;
+        }
+
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            let j = *indices.entry(next).or_insert_with_key(|next| {
+                states.push(next.clone());
+                costs.push(u64::MAX);
+                previous.push(vec![]);
+                states.len() - 1
+            });
+
+            if next_cost < costs[j] {
+                costs[j] = next_cost;
+                previous[j] = vec![i];
+                queue.push(Reverse((next_cost + heuristic(&states[j]), next_cost, j)));
+            } else if next_cost == costs[j] {
+                previous[j].push(i);
+            }
+        }
+    }
+
+    let paths = goals
+        .into_iter()
+        .flat_map(|goal| paths_to(goal, &previous))
+        .map(|path| path.into_iter().map(|i| states[i].clone()).collect())
+        .collect();
+
+    best.map(|cost| Found { cost, paths })
+}
+
+/// Walks back from `i` along every cheapest predecessor to the start, which has none.
+fn paths_to(i: usize, previous: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    if previous[i].is_empty() {
+        return vec![vec![i]];
+    }
+
+    previous[i]
+        .iter()
+        .flat_map(|&p| paths_to(p, previous))
+        .map(|mut path| {
+            path.push(i);
+            path
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::{Grid2D, Vec2};
+
+    fn open(grid: &Grid2D<char>, p: Vec2<usize>) -> Vec<(Vec2<usize>, u64)> {
+        grid.neighbors4(p)
+            .filter(|(_, c)| **c != '#')
+            .map(|(p, _)| (p, 1))
+            .collect()
+    }
+
+    #[test]
+    fn dijkstra_shortest_path() {
+        let grid = Grid2D::from("...\n.#.\n...".chars());
+
+        let found = dijkstra(Vec2(0, 0), |p| open(&grid, *p), |p| *p == Vec2(2, 2))
+            .expect("a path to the far corner");
+
+        assert_eq!(found.cost, 4);
+        assert_eq!(found.paths.len(), 2);
+        assert_eq!(found.states().len(), 8);
+        for path in found.paths {
+            assert_eq!(path.first(), Some(&Vec2(0, 0)));
+            assert_eq!(path.last(), Some(&Vec2(2, 2)));
+            assert_eq!(path.len(), 5);
+        }
+    }
+
+    #[test]
+    fn dijkstra_unreachable() {
+        let grid = Grid2D::from("..#\n.#.\n#..".chars());
+
+        assert_eq!(
+            dijkstra(Vec2(0, 0), |p| open(&grid, *p), |p| *p == Vec2(2, 2)),
+            None
+        );
+    }
+
+    #[test]
+    fn dijkstra_with_facing() {
+        // Moving forward costs 1 and turning costs 1000, so the route along the top with a single
+        // turn beats the one down the side with two.
+        let grid = Grid2D::from("....\n.##.\n....".chars());
+        let successors = |&(p, d): &(Vec2<usize>, Vec2<isize>)| {
+            let mut next = vec![
+                ((p, d.rotate_clockwise()), 1000),
+                ((p, -d.rotate_clockwise()), 1000),
+            ];
+            if let Some(p) = p.try_add(d, grid.max()) {
+                if grid.get(p) != Some(&'#') {
+                    next.push(((p, d), 1));
+                }
+            }
+            next
+        };
+
+        let found = dijkstra((Vec2(0, 0), Vec2(1, 0)), successors, |(p, _)| {
+            *p == Vec2(3, 2)
+        })
+        .expect("a path to the far corner");
+
+        assert_eq!(found.cost, 1005);
+        assert_eq!(found.paths.len(), 1);
+        assert_eq!(found.paths[0].len(), 7);
+        assert_eq!(found.paths[0][4], (Vec2(3, 0), Vec2(0, 1)));
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let grid = Grid2D::from(".....\n.###.\n...#.\n.#...\n.....".chars());
+        let goal = Vec2(4, 4);
+        let manhattan = |p: &Vec2<usize>| p.manhattan(&goal) as u64;
+
+        let expected = dijkstra(Vec2(0, 0), |p| open(&grid, *p), |p| *p == goal);
+        let found = astar(Vec2(0, 0), |p| open(&grid, *p), |p| *p == goal, manhattan);
+
+        assert_eq!(found.as_ref().map(|f| f.cost), Some(8));
+        assert_eq!(
+            found.map(|f| f.states().len()),
+            expected.map(|f| f.states().len())
+        );
+    }
+}
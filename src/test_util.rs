@@ -2,8 +2,9 @@ use std::io::{BufRead, BufReader, Read};
 
 #[macro_export]
 macro_rules! test_solution {
-    ($part_fn:ident $test_name:ident $input:expr , $expected:expr) => {
+    ($(#[$attr:meta])* $part_fn:ident $test_name:ident $input:expr , $expected:expr) => {
         #[test]
+        $(#[$attr])*
         fn $test_name() {
             let input = $crate::test_util::StringBufRead::from($input);
 
@@ -21,8 +22,6 @@ macro_rules! assert_matches {
     };
 }
 
-pub use test_solution;
-
 pub struct StringBufRead<'a>(BufReader<StringReader<'a>>);
 
 impl<'a> From<&'a str> for StringBufRead<'a> {
@@ -0,0 +1,122 @@
+use anyhow::anyhow;
+
+/// A single `key = value` pair from a TOML document, along with the `[section]` it appeared in.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub section: Option<String>,
+    pub key: String,
+    pub value: String,
+}
+
+/// Parse the small subset of TOML used by the CLI's config and manifest files: `[section]`
+/// headers, `key = value` pairs, blank lines and `#` comments, including after a value. Quoted string values are unquoted,
+/// other values (integers, booleans) are kept as written.
+pub fn parse(s: &str) -> anyhow::Result<Vec<Entry>> {
+    let mut section = None;
+    let mut entries = vec![];
+
+    for (i, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = Some(name.trim().to_string());
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or(anyhow!("line {}: expected `key = value`", i + 1))?;
+        let value = value.trim();
+
+        // A trailing `# comment` may follow the value, but a `#` inside a string is part of it.
+        let value = if let Some(quoted) = value.strip_prefix('"') {
+            let (quoted, rest) = quoted
+                .split_once('"')
+                .ok_or(anyhow!("line {}: unterminated string", i + 1))?;
+            let rest = rest.trim();
+            if !rest.is_empty() && !rest.starts_with('#') {
+                return Err(anyhow!("line {}: unexpected text after string", i + 1));
+            }
+            quoted
+        } else {
+            let value = value.split_once('#').map_or(value, |(v, _)| v).trim();
+            if value.is_empty() || value.contains(char::is_whitespace) {
+                return Err(anyhow!("line {}: expected a value", i + 1));
+            }
+            value
+        };
+
+        entries.push(Entry {
+            section: section.clone(),
+            key: key.trim().to_string(),
+            value: value.to_string(),
+        });
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn toml_parse_sections_and_values() {
+        let entries = parse(
+            "top = \"level\"
+# a comment
+
+[day1]
+part1 = 11
+part2 = \"31\"
+",
+        )
+        .expect("document to parse");
+
+        assert_eq!(
+            entries,
+            vec![
+                Entry {
+                    section: None,
+                    key: "top".into(),
+                    value: "level".into()
+                },
+                Entry {
+                    section: Some("day1".into()),
+                    key: "part1".into(),
+                    value: "11".into()
+                },
+                Entry {
+                    section: Some("day1".into()),
+                    key: "part2".into(),
+                    value: "31".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn toml_parse_rejects_malformed_lines() {
+        assert!(parse("no equals sign").is_err());
+        assert!(parse("key = \"unterminated").is_err());
+        assert!(parse("key = two words").is_err());
+        assert!(parse("key = \"quoted\" trailing").is_err());
+    }
+
+    #[test]
+    fn toml_parse_inline_comments() {
+        let entries = parse(
+            "color = \"never\"  # ci
+part1 = 11 # answer
+hash = \"a # b\"
+",
+        )
+        .expect("document to parse");
+
+        let values: Vec<_> = entries.iter().map(|e| e.value.as_str()).collect();
+        assert_eq!(values, ["never", "11", "a # b"]);
+    }
+}